@@ -1,5 +1,3 @@
-use recol_lib;
-
 const FETCH_GHOSSTY_THEMES: bool = false;
 const BUILD_COLORSCHEMES_BIN: bool = false;
 
@@ -1,4 +1,4 @@
-use crate::{Color, ColorScheme, CssColor};
+use crate::{Color, ColorScheme, ColorVisionDeficiency, CssColor};
 
 /// Represents a group of theme colors that can be selected for operations.
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
@@ -64,6 +64,17 @@ impl std::str::FromStr for ThemeColorGroup {
 }
 
 impl ThemeColorGroup {
+    /// Like [`Self::select_colors`], but never includes `bg`.
+    fn select_colors_except_bg<'a>(&self, cs: &'a mut ColorScheme) -> Vec<&'a mut CssColor> {
+        let with_bg = matches!(
+            self,
+            Self::All | Self::UI | Self::Background | Self::BaseBackground
+        );
+        // Every group holding `bg` lists it first.
+        let skip = usize::from(with_bg);
+        self.select_colors(cs).into_iter().skip(skip).collect()
+    }
+
     pub fn select_colors<'a>(&self, cs: &'a mut ColorScheme) -> Vec<&'a mut CssColor> {
         match self {
             ThemeColorGroup::All => vec![
@@ -229,7 +240,8 @@ pub enum ThemeAdjustment {
 
     /// Shifts lightness uniformly for all colors in the group.
     /// `-100..100`: negative darkens, positive lightens.
-    Brightness(ThemeColorGroup, f32),
+    /// HSL lightness, or OKLCH lightness with `@oklch`.
+    Brightness(ThemeColorGroup, f32, ColorSpace),
 
     /// Applies a nonlinear midtone curve; endpoints (black/white) stay fixed.
     /// `-100..100`: negative darkens midtones, positive brightens them.
@@ -242,7 +254,8 @@ pub enum ThemeAdjustment {
 
     /// Scales saturation uniformly for all colors in the group.
     /// `-100..100`: negative desaturates, positive saturates.
-    Saturation(ThemeColorGroup, f32),
+    /// HSV saturation, or OKLCH chroma (`+100` doubles it) with `@oklch`.
+    Saturation(ThemeColorGroup, f32, ColorSpace),
 
     /// Scales saturation non-uniformly: weakly saturated colors are boosted
     /// more, near-neutral grays and already-vivid colors are protected.
@@ -251,7 +264,8 @@ pub enum ThemeAdjustment {
 
     /// Rotates hue by an angle for all colors in the group.
     /// `-100..100`, mapped to `-180°..180°`.
-    Hue(ThemeColorGroup, f32),
+    /// HSV hue, or OKLCH hue with `@oklch`, which keeps perceived lightness.
+    Hue(ThemeColorGroup, f32, ColorSpace),
 
     /// Shifts colors along the blue↔yellow axis (Lab b*).
     /// `-100..100`: negative cools (blue), positive warms (yellow).
@@ -265,13 +279,29 @@ pub enum ThemeAdjustment {
     /// and/or chroma, reducing visual imbalance within the palette.
     /// `-100..100`: strength of the pull toward the mean (0 = no change).
     /// `NormalizeChannel` selects whether lightness, chroma, or both are affected.
-    Normalize(ThemeColorGroup, f32, NormalizeChannel),
+    /// HSL lightness/saturation, or OKLCH lightness/chroma with `@oklch`.
+    Normalize(ThemeColorGroup, f32, NormalizeChannel, ColorSpace),
 
     /// Flips lightness around a pivot while preserving hue and saturation,
     /// e.g. to derive a light theme from a dark one.
     /// `-100..100`: blend strength toward the fully inverted color (sign ignored).
     /// Optional pivot lightness (`0..100`, default `50`) to invert around.
     Invert(ThemeColorGroup),
+
+    /// Moves Lab lightness of each color just far enough to reach the given
+    /// WCAG contrast ratio against `bg`, keeping a* and b* (hue) fixed.
+    /// `1..21`, e.g. `4.5` for AA. Colors already above it are untouched;
+    /// `bg` itself is skipped.
+    MinContrast(ThemeColorGroup, f32),
+
+    /// Shows the colors as seen with a color vision deficiency.
+    /// `0..100`: severity, from normal vision to dichromacy.
+    SimulateCvd(ThemeColorGroup, f32, ColorVisionDeficiency),
+
+    /// Shifts colors so that hues confused with the given deficiency stay
+    /// apart, e.g. diff red and green for deutans. Neutrals are unchanged.
+    /// `0..100`: strength of the shift.
+    Daltonize(ThemeColorGroup, f32, ColorVisionDeficiency),
 }
 
 impl std::str::FromStr for ThemeAdjustment {
@@ -292,42 +322,82 @@ impl std::str::FromStr for ThemeAdjustment {
             .parse::<f32>()
             .map_err(|_| ParseThemeAdjustmentError(format!("invalid number: '{}'", value_str)))?;
 
-        let mut key_parts = key.trim().splitn(2, '.');
-        let first = key_parts.next().unwrap();
-        let (group, adjust) = if let Some(second) = key_parts.next() {
-            (
-                first.parse::<ThemeColorGroup>().map_err(|_| {
-                    ParseThemeAdjustmentError(format!("unknown group: '{}'", first))
+        let (key, space) = match key.trim().split_once('@') {
+            Some((key, space)) => (
+                key,
+                space.parse::<ColorSpace>().map_err(|_| {
+                    ParseThemeAdjustmentError(format!("unknown color space: '{space}'"))
                 })?,
-                second,
-            )
-        } else {
-            (ThemeColorGroup::All, first)
+            ),
+            None => (key, ColorSpace::default()),
+        };
+
+        let key = key.trim();
+        let (group, adjust) = match key.split_once('.') {
+            Some((first, second)) => match first.parse::<ThemeColorGroup>() {
+                Ok(group) => (group, second),
+                // `cvd.protan`, `daltonize.tritan`: no group given.
+                Err(_) if matches!(first, "cvd" | "daltonize" | "dz") => {
+                    (ThemeColorGroup::All, key)
+                }
+                Err(_) => {
+                    return Err(ParseThemeAdjustmentError(format!(
+                        "unknown group: '{}'",
+                        first
+                    )));
+                }
+            },
+            None => (ThemeColorGroup::All, key),
         };
 
-        Ok(match adjust {
-            "b" | "brightness" => Self::Brightness(group, value),
+        let cvd = |kind: &str| {
+            kind.parse::<ColorVisionDeficiency>().map_err(|_| {
+                ParseThemeAdjustmentError(format!("unknown color vision deficiency: '{kind}'"))
+            })
+        };
+
+        let adjustment = match adjust {
+            "b" | "brightness" => Self::Brightness(group, value, space),
             "e" | "exposure" => Self::Exposure(group, value),
             "c" | "contrast" => Self::Contrast(group, value, None),
-            "s" | "sat" | "saturation" => Self::Saturation(group, value),
+            "s" | "sat" | "saturation" => Self::Saturation(group, value, space),
             "v" | "vib" | "vibrance" => Self::Vibrance(group, value),
-            "h" | "hue" => Self::Hue(group, value),
+            "h" | "hue" => Self::Hue(group, value, space),
             "t" | "temp" | "temperature" => Self::Temperature(group, value),
             "ti" | "tint" => Self::Tint(group, value),
             "g" | "gamma" => Self::Gamma(group, value),
             "f" | "fade" => Self::Fade(group, value),
             "i" | "invert" => Self::Invert(group),
+            "mc" | "min-contrast" => Self::MinContrast(group, value),
+            "dz" | "daltonize" => Self::Daltonize(group, value, ColorVisionDeficiency::Deutan),
+            _ if adjust.starts_with("cvd.") => Self::SimulateCvd(group, value, cvd(&adjust[4..])?),
+            _ if adjust.starts_with("dz.") || adjust.starts_with("daltonize.") => {
+                let (_, kind) = adjust.split_once('.').unwrap_or_default();
+                Self::Daltonize(group, value, cvd(kind)?)
+            }
             "n" | "norm" | "normalize" => {
-                Self::Normalize(group, value, NormalizeChannel::Lightness)
+                Self::Normalize(group, value, NormalizeChannel::Lightness, space)
             }
-            "nb" | "norm-both" => Self::Normalize(group, value, NormalizeChannel::Both),
-            "nc" | "norm-chroma" => Self::Normalize(group, value, NormalizeChannel::Chroma),
+            "nb" | "norm-both" => Self::Normalize(group, value, NormalizeChannel::Both, space),
+            "nc" | "norm-chroma" => Self::Normalize(group, value, NormalizeChannel::Chroma, space),
             _ => {
                 return Err(ParseThemeAdjustmentError(format!(
                     "unknown adjustment: '{adjust}'",
                 )));
             }
-        })
+        };
+
+        let supports_space = matches!(
+            adjustment,
+            Self::Brightness(..) | Self::Saturation(..) | Self::Hue(..) | Self::Normalize(..)
+        );
+        if space != ColorSpace::default() && !supports_space {
+            return Err(ParseThemeAdjustmentError(format!(
+                "'{adjust}' does not support @{space}",
+            )));
+        }
+
+        Ok(adjustment)
     }
 }
 
@@ -365,11 +435,16 @@ impl ThemeAdjustment {
                 for_each_rgb(group, cs, |r, g, b| (adjust(r), adjust(g), adjust(b)));
             }
 
-            ThemeAdjustment::Brightness(group, v) => {
+            ThemeAdjustment::Brightness(group, v, ColorSpace::Default) => {
                 let delta = pct(*v);
                 for_each_hsl(group, cs, |h, s, l| (h, s, (l + delta).clamp(0.0, 100.0)));
             }
 
+            ThemeAdjustment::Brightness(group, v, ColorSpace::Oklch) => {
+                let delta = pct(*v) / 100.0;
+                for_each_oklch(group, cs, |l, c, h| ((l + delta).clamp(0.0, 1.0), c, h));
+            }
+
             ThemeAdjustment::Gamma(group, v) => {
                 // v = 100 -> exponent 0.5 (brighten midtones)
                 // v = -100 -> exponent 2.0 (darken midtones)
@@ -389,7 +464,12 @@ impl ThemeAdjustment {
                 });
             }
 
-            ThemeAdjustment::Saturation(group, v) => {
+            ThemeAdjustment::Saturation(group, v, ColorSpace::Oklch) => {
+                let factor = 1.0 + pct(*v) / 100.0;
+                for_each_oklch(group, cs, |l, c, h| (l, c * factor, h));
+            }
+
+            ThemeAdjustment::Saturation(group, v, ColorSpace::Default) => {
                 let v = pct(*v);
                 for_each_hsv(group, cs, |h, s, val| {
                     let new_s = if v >= 0.0 {
@@ -416,11 +496,16 @@ impl ThemeAdjustment {
                 });
             }
 
-            ThemeAdjustment::Hue(group, v) => {
+            ThemeAdjustment::Hue(group, v, space) => {
                 let degrees = pct(*v) / 100.0 * 180.0;
-                for_each_hsv(group, cs, |h, s, val| {
-                    ((h + degrees).rem_euclid(360.0), s, val)
-                });
+                match space {
+                    ColorSpace::Default => for_each_hsv(group, cs, |h, s, val| {
+                        ((h + degrees).rem_euclid(360.0), s, val)
+                    }),
+                    ColorSpace::Oklch => {
+                        for_each_oklch(group, cs, |l, c, h| (l, c, (h + degrees).rem_euclid(360.0)))
+                    }
+                }
             }
 
             ThemeAdjustment::Temperature(group, v) => {
@@ -434,41 +519,74 @@ impl ThemeAdjustment {
                 for_each_lab(group, cs, |l, a, b| (l, a + shift, b));
             }
 
-            ThemeAdjustment::Normalize(group, v, channel) => {
+            ThemeAdjustment::Normalize(group, v, channel, space) => {
                 let strength = pct(*v) / 100.0;
 
+                // (lightness, saturation or chroma, hue)
                 let samples: Vec<(f32, f32, f32)> = group
                     .select_colors(cs)
                     .iter()
-                    .map(|css| css.color().hsl())
+                    .map(|css| match space {
+                        ColorSpace::Default => {
+                            let (h, s, l) = css.color().hsl();
+                            (l, s, h)
+                        }
+                        ColorSpace::Oklch => css.color().oklch(),
+                    })
                     .collect();
                 if samples.is_empty() {
                     return;
                 }
 
-                let mean_l = samples.iter().map(|(_, _, l)| l).sum::<f32>() / samples.len() as f32;
+                let mean_l = samples.iter().map(|(l, _, _)| l).sum::<f32>() / samples.len() as f32;
                 let mean_s = samples.iter().map(|(_, s, _)| s).sum::<f32>() / samples.len() as f32;
 
-                for (css, (h, s, l)) in group.select_colors(cs).into_iter().zip(samples) {
+                for (css, (l, s, h)) in group.select_colors(cs).into_iter().zip(samples) {
                     let new_l = match channel {
                         NormalizeChannel::Lightness | NormalizeChannel::Both => {
-                            (l + (mean_l - l) * strength).clamp(0.0, 100.0)
+                            l + (mean_l - l) * strength
                         }
                         NormalizeChannel::Chroma => l,
                     };
                     let new_s = match channel {
                         NormalizeChannel::Chroma | NormalizeChannel::Both => {
-                            (s + (mean_s - s) * strength).clamp(0.0, 100.0)
+                            s + (mean_s - s) * strength
                         }
                         NormalizeChannel::Lightness => s,
                     };
-                    *css = Color::from_hsl(h, new_s, new_l).css();
+                    *css = match space {
+                        ColorSpace::Default => Color::from_hsl(h, new_s, new_l),
+                        ColorSpace::Oklch => Color::from_oklch(new_l, new_s, h),
+                    }
+                    .css();
                 }
             }
 
             ThemeAdjustment::Invert(group) => {
                 for_each_hsl(group, cs, |h, s, l| (h, s, 100.0 - l));
             }
+
+            ThemeAdjustment::MinContrast(group, ratio) => {
+                let target = ratio.clamp(1.0, 21.0);
+                let bg = cs.bg.color();
+                for css in group.select_colors_except_bg(cs) {
+                    *css = min_contrast(css.color(), &bg, target).css();
+                }
+            }
+
+            ThemeAdjustment::SimulateCvd(group, v, kind) => {
+                let severity = v.clamp(0.0, 100.0) / 100.0;
+                for css in group.select_colors(cs) {
+                    *css = css.color().simulate_cvd(*kind, severity).css();
+                }
+            }
+
+            ThemeAdjustment::Daltonize(group, v, kind) => {
+                let strength = v.clamp(0.0, 100.0) / 100.0;
+                for css in group.select_colors(cs) {
+                    *css = css.color().daltonize(*kind, strength).css();
+                }
+            }
         }
     }
 }
@@ -518,6 +636,20 @@ fn for_each_lab(
     }
 }
 
+/// Applies `f` to each color's OKLCH components and writes the result back,
+/// reducing chroma for colors that end up outside sRGB.
+fn for_each_oklch(
+    group: &ThemeColorGroup,
+    cs: &mut ColorScheme,
+    mut f: impl FnMut(f32, f32, f32) -> (f32, f32, f32),
+) {
+    for css in group.select_colors(cs) {
+        let (l, c, h) = css.color().oklch();
+        let (l, c, h) = f(l, c, h);
+        *css = Color::from_oklch(l, c, h).css();
+    }
+}
+
 /// Applies `f` to each color's raw RGB channels and writes the result back.
 fn for_each_rgb(
     group: &ThemeColorGroup,
@@ -531,13 +663,176 @@ fn for_each_rgb(
     }
 }
 
+/// Smallest Lab lightness change that gives `c` at least `target` contrast
+/// against `bg`. Prefers moving further away from the background's lightness
+/// on the side `c` is already on, and crosses to the other side, or falls
+/// back to the best endpoint, if the target is out of reach.
+fn min_contrast(c: Color, bg: &Color, target: f32) -> Color {
+    if c.contrast_ratio(bg) >= target {
+        return c;
+    }
+    let (l, a, b) = c.lab();
+    let bg_l = bg.lab().0;
+    // Round-trip through CSS so 8-bit quantization can't drop below target.
+    let at = |l: f32| Color::from_lab(l, a, b).css().color();
+
+    let lighter = l > bg_l || (l == bg_l && bg_l < 50.0);
+    let (first, second) = if lighter { (100.0, 0.0) } else { (0.0, 100.0) };
+    let Some(end) = [first, second]
+        .into_iter()
+        .find(|&end| at(end).contrast_ratio(bg) >= target)
+    else {
+        return [at(first), at(second)]
+            .into_iter()
+            .max_by(|x, y| x.contrast_ratio(bg).total_cmp(&y.contrast_ratio(bg)))
+            .unwrap_or(c);
+    };
+
+    // Contrast grows monotonically with the distance from `bg_l`, so start
+    // at `l` when it is on the side of `end`, and at `bg_l` otherwise.
+    let start = if end > bg_l { l.max(bg_l) } else { l.min(bg_l) };
+    let (mut lo, mut hi) = (start, end);
+    for _ in 0..24 {
+        let mid = (lo + hi) / 2.0;
+        if at(mid).contrast_ratio(bg) >= target {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    at(hi)
+}
+
 /// Scales the -100..100 input range onto a perceptually reasonable
 /// Lab a*/b* excursion (empirically ~±80 covers the practical gamut).
 const TEMP_TINT_SCALE: f32 = 0.8;
 
+/// Color space some adjustments can run in, selected with an `@space`
+/// suffix, e.g. `pal.hue@oklch=30`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ColorSpace {
+    /// The HSL/HSV model documented on each adjustment.
+    #[default]
+    Default,
+    /// Perceptual OKLCH; out-of-gamut results lose chroma, not lightness.
+    Oklch,
+}
+
+impl std::str::FromStr for ColorSpace {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim() {
+            "oklch" => Ok(Self::Oklch),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for ColorSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::Oklch => write!(f, "oklch"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum NormalizeChannel {
     Lightness,
     Chroma,
     Both,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::COLOR_SCHEME_NC;
+
+    #[test]
+    fn min_contrast_reaches_target_and_keeps_others() {
+        let mut colors = [Color::from_hex(0xdddddd); COLOR_SCHEME_NC];
+        colors[0] = Color::from_hex(0x101010);
+        colors[10] = Color::from_hex(0x1a237e);
+        let mut cs = ColorScheme::from_color_slice(&colors);
+        let bg = cs.bg.clone();
+        let green = cs.base.green.clone();
+
+        "pal.min-contrast=4.5"
+            .parse::<ThemeAdjustment>()
+            .unwrap()
+            .apply(&mut cs);
+
+        let blue = cs.base.blue.color();
+        assert!(blue.contrast_ratio(&bg.color()) >= 4.5);
+        assert!(blue.contrast_ratio(&bg.color()) < 4.7);
+        assert!(blue.b > blue.r && blue.b > blue.g);
+        assert_eq!(cs.base.green, green);
+        assert_eq!(cs.bg, bg);
+    }
+
+    #[test]
+    fn min_contrast_moves_away_from_bg_on_either_side() {
+        let bg = Color::from_hex(0x555555);
+        let bg_l = bg.lab().0;
+
+        // Darker than bg, and black is far enough: stay below bg.
+        let c = min_contrast(Color::from_hex(0x404040), &bg, 2.5);
+        assert!(c.contrast_ratio(&bg) >= 2.5);
+        assert!(c.lab().0 < Color::from_hex(0x404040).lab().0);
+
+        // Nothing below bg reaches 4.5: cross over, just far enough.
+        let c = min_contrast(Color::from_hex(0x404040), &bg, 4.5);
+        assert!(c.contrast_ratio(&bg) >= 4.5);
+        assert!(c.contrast_ratio(&bg) < 4.7);
+        assert!(c.lab().0 > bg_l);
+    }
+
+    #[test]
+    fn oklch_hue_keeps_lightness() {
+        let c = Color::from_hex(0x7aa2f7);
+        let (l, a, b) = c.oklab();
+        assert_eq!(Color::from_oklab(l, a, b).css(), c.css());
+
+        let mut colors = [c; COLOR_SCHEME_NC];
+        colors[0] = Color::from_hex(0x1a1b26);
+        let mut cs = ColorScheme::from_color_slice(&colors);
+        "pal.hue@oklch=60"
+            .parse::<ThemeAdjustment>()
+            .unwrap()
+            .apply(&mut cs);
+
+        let (l2, _, h2) = cs.base.red.color().oklch();
+        assert!((l2 - l).abs() < 0.01);
+        let expected = (c.oklch().2 + 108.0).rem_euclid(360.0);
+        assert!((h2 - expected).abs() < 2.0);
+        assert!("pal.fade@oklch=10".parse::<ThemeAdjustment>().is_err());
+        assert!("pal.hue@lab=10".parse::<ThemeAdjustment>().is_err());
+    }
+
+    #[test]
+    fn parses_cvd_and_daltonize() {
+        use ColorVisionDeficiency::*;
+        let parse = |s: &str| s.parse::<ThemeAdjustment>();
+        assert_eq!(
+            parse("cvd.protan=100").unwrap(),
+            ThemeAdjustment::SimulateCvd(ThemeColorGroup::All, 100.0, Protan)
+        );
+        assert_eq!(
+            parse("pal.cvd.tritan=50").unwrap(),
+            ThemeAdjustment::SimulateCvd(ThemeColorGroup::Palette, 50.0, Tritan)
+        );
+        assert_eq!(
+            parse("pal.daltonize=80").unwrap(),
+            ThemeAdjustment::Daltonize(ThemeColorGroup::Palette, 80.0, Deutan)
+        );
+        assert_eq!(
+            parse("daltonize.protan=100").unwrap(),
+            ThemeAdjustment::Daltonize(ThemeColorGroup::All, 100.0, Protan)
+        );
+        assert!(parse("cvd.achroma=100").is_err());
+        assert!(parse("cvd.protan@oklch=100").is_err());
+        assert!(parse("foo.hue=10").is_err());
+    }
+}
@@ -23,11 +23,23 @@
 //!
 //! Themes are sorted alphabetically by name at build time.
 //! All byte sequences are guaranteed valid; panics on corruption are intentional.
+//!
+//! # User themes
+//!
+//! Themes loaded at runtime with [`load_user_themes`] are encoded into the
+//! same per-theme layout and leaked for the rest of the process, so they are
+//! served as [`LazyTheme`]s just like the embedded ones. They are iterated
+//! before the embedded themes and shadow embedded themes with the same name.
 
-use crate::{COLOR_SCHEME_NC, COLOR_SCHEME_SIZE, Color, ColorScheme, Theme};
+use crate::{
+    AdvancedColorScheme, COLOR_SCHEME_NC, COLOR_SCHEME_SIZE, COLOR_SIZE, Color, ColorScheme, Theme,
+};
+use serde::Deserialize;
 use std::{
-    io::{BufRead, BufReader, Write},
+    collections::HashSet,
+    io::{self, BufRead, BufReader, Write},
     path::Path,
+    sync::RwLock,
 };
 
 // TODO: remove offst table
@@ -59,6 +71,74 @@ fn theme_raw(i: usize) -> &'static [u8] {
     &COLOR_SCHEMES[themes_section_start() + theme_section_offset(i)..]
 }
 
+/// Raw bytes of user themes, each in the per-theme layout.
+static USER_THEMES: RwLock<Vec<&'static [u8]>> = RwLock::new(Vec::new());
+
+#[inline]
+fn user_theme_count() -> usize {
+    USER_THEMES.read().map(|t| t.len()).unwrap_or(0)
+}
+
+#[inline]
+fn user_theme_raw(i: usize) -> Option<&'static [u8]> {
+    USER_THEMES.read().ok()?.get(i).copied()
+}
+
+/// Register `theme` as a user theme, replacing a user theme with the same
+/// name. Names longer than 255 bytes cannot be encoded and are rejected.
+pub fn add_user_theme(theme: &Theme) -> io::Result<()> {
+    if theme.name.is_empty() || theme.name.len() > u8::MAX as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid theme name length: {}", theme.name.len()),
+        ));
+    }
+    let bytes: &'static [u8] = Box::leak(theme.bytes().into_boxed_slice());
+    let mut themes = USER_THEMES
+        .write()
+        .map_err(|_| io::Error::other("user themes lock poisoned"))?;
+    themes.retain(|b| LazyTheme::from_bytes(b).name != theme.name);
+    themes.push(bytes);
+    themes.sort_by(|a, b| LazyTheme::from_bytes(a).cmp(&LazyTheme::from_bytes(b)));
+    Ok(())
+}
+
+/// Load every theme in `dir` as a user theme and return how many were loaded.
+///
+/// Files ending in `.json` are parsed as recol JSON (either a serialized
+/// [`Theme`] or the `--json` output); anything else is parsed as a Ghostty
+/// theme named after its file. Hidden files are skipped, and so are files
+/// that fail to parse, with a warning on stderr. A missing directory yields
+/// `Ok(0)`.
+pub fn load_user_themes(dir: impl AsRef<Path>) -> io::Result<usize> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e),
+    };
+
+    let mut count = 0;
+    for entry in entries {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if file_name.starts_with('.') || !path.is_file() {
+            continue;
+        }
+        let theme = match file_name.strip_suffix(".json") {
+            Some(stem) => parse_json_theme(&path, stem),
+            None => parse_ghostty_theme(&path, file_name),
+        };
+        match theme.and_then(|t| add_user_theme(&t)) {
+            Ok(()) => count += 1,
+            Err(e) => eprintln!("skipping user theme {}: {e}", path.display()),
+        }
+    }
+
+    Ok(count)
+}
+
 /// A theme whose color data is not yet decoded — only name and light/dark flag
 /// are held as direct references into the embedded binary.
 #[derive(Debug, Clone, Copy)]
@@ -104,6 +184,12 @@ impl LazyTheme {
         }
     }
 
+    /// Decode only the `i`-th stored color, in [`ColorScheme`] binary order.
+    fn color(&self, i: usize) -> Color {
+        Color::try_from(&self.color_scheme_bytes[i * COLOR_SIZE..][..COLOR_SIZE])
+            .expect("color bytes are always valid")
+    }
+
     /// Decode the full [`Theme`], including its color data.
     pub fn into_theme(self) -> Theme {
         Theme::new(
@@ -139,10 +225,35 @@ pub enum ThemeFilter<'a> {
     ContainsLower(&'a str),
     StartWithLower(&'a str),
 
+    /// Background within the given CIEDE2000 distance of a color.
+    BgNear(Color, f32),
+    /// Background CIE L* lightness in `[min, max]`.
+    BgLightness(f32, f32),
+    /// WCAG contrast of fg against bg in `[min, max]`.
+    Contrast(f32, f32),
+    /// Warm themes: bg and fg lean yellow/red (mean Lab b* above
+    /// [`TEMPERATURE_THRESHOLD`]).
+    Warm,
+    /// Cool themes: bg and fg lean blue (mean Lab b* below
+    /// `-TEMPERATURE_THRESHOLD`).
+    Cool,
+
     /// Arbitrary predicate.
     Custom(fn(&LazyTheme) -> bool),
 }
 
+/// Default distance for `bg~COLOR` in [`parse_theme_filters`].
+pub const BG_NEAR_DISTANCE: f32 = 10.0;
+
+/// Mean Lab b* of bg and fg a theme needs to count as warm or cool; themes
+/// in between are neutral.
+pub const TEMPERATURE_THRESHOLD: f32 = 2.0;
+
+/// Mean Lab b* of bg and fg.
+fn temperature(t: &LazyTheme) -> f32 {
+    (t.color(0).lab().2 + t.color(1).lab().2) / 2.0
+}
+
 impl<'a> ThemeFilter<'a> {
     #[inline]
     pub fn matches(&self, t: &LazyTheme) -> bool {
@@ -154,6 +265,13 @@ impl<'a> ThemeFilter<'a> {
             Self::StartWith(s) => t.name.starts_with(s),
             Self::ContainsLower(s) => t.name.to_lowercase().contains(s),
             Self::StartWithLower(s) => t.name.to_lowercase().starts_with(s),
+            Self::BgNear(c, distance) => t.color(0).delta_e(c) <= *distance,
+            Self::BgLightness(min, max) => (*min..=*max).contains(&t.color(0).lab().0),
+            Self::Contrast(min, max) => {
+                (*min..=*max).contains(&t.color(1).contrast_ratio(&t.color(0)))
+            }
+            Self::Warm => temperature(t) > TEMPERATURE_THRESHOLD,
+            Self::Cool => temperature(t) < -TEMPERATURE_THRESHOLD,
             Self::Custom(f) => f(t),
         }
     }
@@ -164,21 +282,126 @@ impl<'a> ThemeFilter<'a> {
     }
 }
 
-/// Lazy, zero-allocation iterator over the embedded theme collection.
+#[derive(Debug, Clone)]
+pub struct ParseThemeFilterError(String);
+
+impl std::fmt::Display for ParseThemeFilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for ParseThemeFilterError {}
+
+/// Parse comma-separated color constraints, e.g. `bg~#1e1e2e,contrast>7`.
+///
+/// ```text
+/// dark, light, warm, cool
+/// bg~COLOR[:D]         bg within CIEDE2000 distance D of COLOR (default 10)
+/// l>N, l<N             bg lightness (CIE L*, 0..100)
+/// contrast>N, c<N      fg/bg contrast ratio (1..21)
+/// ```
+///
+/// `>=` and `<=` are accepted as well; the bounds are inclusive either way.
+pub fn parse_theme_filters(
+    s: &str,
+) -> std::result::Result<Vec<ThemeFilter<'static>>, ParseThemeFilterError> {
+    s.split(',').map(str::parse).collect()
+}
+
+impl std::str::FromStr for ThemeFilter<'_> {
+    type Err = ParseThemeFilterError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.to_lowercase().as_str() {
+            "dark" => return Ok(Self::Dark),
+            "light" => return Ok(Self::Light),
+            "warm" => return Ok(Self::Warm),
+            "cool" => return Ok(Self::Cool),
+            _ => {}
+        }
+
+        let err = |msg: String| ParseThemeFilterError(msg);
+        let number = |v: &str| {
+            v.trim()
+                .parse::<f32>()
+                .map_err(|_| err(format!("invalid number: '{}'", v.trim())))
+        };
+
+        if let Some((key, value)) = s.split_once('~') {
+            if !matches!(key.trim(), "bg" | "background") {
+                return Err(err(format!("'~' only applies to bg: '{s}'")));
+            }
+            let (color, distance) = match value.split_once(':') {
+                Some((color, distance)) => (color, number(distance)?),
+                None => (value, BG_NEAR_DISTANCE),
+            };
+            let color = crate::parse_color_value(color)
+                .ok_or_else(|| err(format!("invalid color: '{}'", color.trim())))?;
+            return Ok(Self::BgNear(color, distance));
+        }
+
+        let Some(i) = s.find(['<', '>']) else {
+            return Err(err(format!("unknown filter: '{s}'")));
+        };
+        let (key, op, value) = (
+            s[..i].trim(),
+            &s[i..i + 1],
+            s[i + 1..].trim_start_matches('='),
+        );
+        let value = number(value)?;
+        let (min, max) = if op == ">" {
+            (value, f32::INFINITY)
+        } else {
+            (f32::NEG_INFINITY, value)
+        };
+        match key {
+            "c" | "contrast" => Ok(Self::Contrast(min, max)),
+            "l" | "lightness" => Ok(Self::BgLightness(min, max)),
+            _ => Err(err(format!("unknown filter key: '{key}'"))),
+        }
+    }
+}
+
+/// Per-color weights of [`Collection::similar_to`], in [`ColorScheme`]
+/// binary order: bg and fg dominate how a theme looks, selection and cursor
+/// matter least.
+pub const SIMILARITY_WEIGHTS: [f32; COLOR_SCHEME_NC] = [
+    6.0, 3.0, // bg, fg
+    0.5, 0.5, 0.5, 0.5, // selection, cursor
+    1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, // base ANSI
+    1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, // bright ANSI
+];
+
+/// Lazy iterator over the user and embedded themes. Only the names of user
+/// themes are collected up front; without user themes it does not allocate.
 ///
 /// Call [`Collection::new`] to start from the beginning, then use the
 /// standard [`Iterator`] API or the convenience methods below.
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Collection {
-    total: usize,
+    user: usize,
+    embedded: usize,
+    /// Names of the user themes, which hide embedded themes of the same name.
+    shadowed: HashSet<&'static str>,
     index: usize,
 }
 
 impl Collection {
     pub fn new() -> Self {
+        let (user, shadowed) = USER_THEMES
+            .read()
+            .map(|t| {
+                let names = t.iter().map(|b| LazyTheme::from_bytes(b).name);
+                (t.len(), names.collect())
+            })
+            .unwrap_or_default();
         Self {
+            user,
+            embedded: theme_count(),
+            shadowed,
             index: 0,
-            total: theme_count(),
         }
     }
 
@@ -188,9 +411,14 @@ impl Collection {
         self.index = 0;
     }
 
-    /// Look up a theme by its exact position in the sorted list.
+    /// Look up a theme by its exact position: user themes first, then the
+    /// embedded ones (shadowed embedded themes are still addressable here).
     pub fn by_index(i: usize) -> Option<LazyTheme> {
-        (i < theme_count()).then(|| LazyTheme::from_bytes(theme_raw(i)))
+        let user = user_theme_count();
+        if i < user {
+            return user_theme_raw(i).map(LazyTheme::from_bytes);
+        }
+        (i - user < theme_count()).then(|| LazyTheme::from_bytes(theme_raw(i - user)))
     }
 
     /// Look up a theme by its exact name.
@@ -241,6 +469,41 @@ impl Collection {
             .collect()
     }
 
+    /// Rank themes matching `filters` by how close their colors are to `cs`,
+    /// most similar first, and return the best `n` with their distance.
+    ///
+    /// The distance is a weighted mean of the CIEDE2000 difference over the
+    /// stored colors (see [`SIMILARITY_WEIGHTS`]). A theme compared with
+    /// itself scores `0.0`, so callers looking for alternatives should skip it.
+    pub fn similar_to(
+        &mut self,
+        cs: &ColorScheme,
+        filters: &[ThemeFilter<'_>],
+        n: usize,
+    ) -> Vec<(LazyTheme, f32)> {
+        let target = cs.as_colors_array().map(|c| c.lab());
+        let total_weight = SIMILARITY_WEIGHTS.iter().sum::<f32>();
+
+        self.reset();
+        let mut ranked = self
+            .filtered(filters)
+            .map(|t| {
+                let colors = t.into_theme().colors.as_colors_array();
+                let distance = colors
+                    .iter()
+                    .zip(&target)
+                    .zip(SIMILARITY_WEIGHTS)
+                    .map(|((c, lab), w)| crate::delta_e2000(c.lab(), *lab) * w)
+                    .sum::<f32>()
+                    / total_weight;
+                (t, distance)
+            })
+            .collect::<Vec<_>>();
+        ranked.sort_by(|a, b| a.1.total_cmp(&b.1));
+        ranked.truncate(n);
+        ranked
+    }
+
     /// Iterator adapter that applies all filters in `filters`.
     pub fn filtered<'a>(
         &'a mut self,
@@ -260,12 +523,22 @@ impl Iterator for Collection {
     type Item = LazyTheme;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.index >= self.total {
-            return None;
+        let user = self.user;
+        while self.index < user + self.embedded {
+            let i = self.index;
+            self.index += 1;
+            if i < user {
+                if let Some(raw) = user_theme_raw(i) {
+                    return Some(LazyTheme::from_bytes(raw));
+                }
+                continue;
+            }
+            let item = LazyTheme::from_bytes(theme_raw(i - user));
+            if !self.shadowed.contains(item.name) {
+                return Some(item);
+            }
         }
-        let item = LazyTheme::from_bytes(theme_raw(self.index));
-        self.index += 1;
-        Some(item)
+        None
     }
 }
 
@@ -316,10 +589,14 @@ pub fn build_colorschemes_bin(
     Ok(())
 }
 
-fn parse_ghostty_theme(path: impl AsRef<Path>, name: &str) -> std::io::Result<Theme> {
+/// Parse a Ghostty theme file (`key = value` lines, `palette = N=#rrggbb`).
+pub fn parse_ghostty_theme(path: impl AsRef<Path>, name: &str) -> io::Result<Theme> {
     let reader = BufReader::new(std::fs::File::open(path)?);
     let mut colors = [Color::default(); COLOR_SCHEME_NC];
 
+    let invalid = |key: &str| io::Error::new(io::ErrorKind::InvalidData, format!("{name}: {key}"));
+    let color = |key: &str, value: &str| value.parse::<Color>().map_err(|_| invalid(key));
+
     for line in reader.lines() {
         let line = line?;
         let mut kv = line.splitn(2, '=');
@@ -329,23 +606,23 @@ fn parse_ghostty_theme(path: impl AsRef<Path>, name: &str) -> std::io::Result<Th
         let (key, value) = (key.trim(), value.trim());
 
         match key {
-            "background" => colors[0] = value.parse().expect("background color"),
-            "foreground" => colors[1] = value.parse().expect("foreground color"),
-            "selection-background" => colors[2] = value.parse().expect("selection-background"),
-            "selection-foreground" => colors[3] = value.parse().expect("selection-foreground"),
-            "cursor-color" => colors[4] = value.parse().expect("cursor-color"),
-            "cursor-text" => colors[5] = value.parse().expect("cursor-text"),
+            "background" => colors[0] = color(key, value)?,
+            "foreground" => colors[1] = color(key, value)?,
+            "selection-background" => colors[2] = color(key, value)?,
+            "selection-foreground" => colors[3] = color(key, value)?,
+            "cursor-color" => colors[4] = color(key, value)?,
+            "cursor-text" => colors[5] = color(key, value)?,
             "palette" => {
                 // Format: `palette = <index>=<#rrggbb>`
                 let mut parts = value.splitn(2, '=');
                 let index: usize = parts
                     .next()
                     .and_then(|s| s.trim().parse().ok())
-                    .expect("palette index");
+                    .ok_or_else(|| invalid("palette index"))?;
                 let color: Color = parts
                     .next()
                     .and_then(|s| s.trim().parse().ok())
-                    .expect("palette color");
+                    .ok_or_else(|| invalid("palette color"))?;
                 // Palette entries 0–15 map to colors[6..22].
                 if index < 16 {
                     colors[6 + index] = color;
@@ -360,6 +637,58 @@ fn parse_ghostty_theme(path: impl AsRef<Path>, name: &str) -> std::io::Result<Th
     Ok(Theme::new(name, is_light, scheme))
 }
 
+/// Accepted shapes of a recol JSON theme.
+#[derive(Deserialize)]
+#[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
+enum JsonTheme {
+    Plain(Theme),
+    Advanced {
+        name: Option<String>,
+        is_light: bool,
+        colors: AdvancedColorScheme,
+    },
+}
+
+/// Parse a recol JSON theme; `default_name` is used when `name` is absent.
+pub fn parse_json_theme(path: impl AsRef<Path>, default_name: &str) -> io::Result<Theme> {
+    theme_from_json(&std::fs::read_to_string(path)?, default_name)
+}
+
+/// Orange and pink are optional in hand-written themes; missing ones are
+/// derived from the palette as for embedded themes, not left black.
+fn theme_from_json(content: &str, default_name: &str) -> io::Result<Theme> {
+    let invalid = |e| io::Error::new(io::ErrorKind::InvalidData, e);
+    let value = serde_json::from_str::<serde_json::Value>(content).map_err(invalid)?;
+    let mut theme = match JsonTheme::deserialize(&value).map_err(invalid)? {
+        JsonTheme::Plain(t) => t,
+        JsonTheme::Advanced {
+            name,
+            is_light,
+            colors,
+        } => Theme::new(
+            name.unwrap_or_else(|| default_name.into()),
+            is_light,
+            colors.into_color_scheme(),
+        ),
+    };
+
+    let derived = ColorScheme::from_color_slice(&theme.colors.as_colors_array());
+    for (key, ansi, derived) in [
+        ("base", &mut theme.colors.base, derived.base),
+        ("bright", &mut theme.colors.bright, derived.bright),
+    ] {
+        let given = &value["colors"][key];
+        if given.get("orange").is_none() {
+            ansi.orange = derived.orange;
+        }
+        if given.get("pink").is_none() {
+            ansi.pink = derived.pink;
+        }
+    }
+    Ok(theme)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -373,4 +702,60 @@ mod tests {
         }
         assert_eq!(decoded, theme_count());
     }
+
+    #[test]
+    fn similar_to_ranks_theme_itself_first() {
+        let nord = Collection::new().by_name("Nord").unwrap().into_theme();
+        let similar = Collection::new().similar_to(&nord.colors, &[], 5);
+        assert_eq!(similar.len(), 5);
+        assert_eq!(similar[0].0.name, "Nord");
+        assert!(similar[0].1 < 0.01);
+        assert!(similar.windows(2).all(|w| w[0].1 <= w[1].1));
+
+        let light = Collection::new().similar_to(&nord.colors, &[ThemeFilter::Light], 3);
+        assert!(light.iter().all(|(t, _)| t.is_light));
+        assert!(light[0].1 > similar[4].1);
+    }
+
+    #[test]
+    fn color_filters_match_decoded_colors() {
+        let filters = parse_theme_filters("bg~#2e3440:3, contrast>=7, l<30").unwrap();
+        let names = Collection::new().name_list(&filters);
+        assert!(names.contains(&"Nord"));
+        for name in names {
+            let cs = Collection::new().by_name(name).unwrap().into_theme().colors;
+            let (bg, fg) = (cs.bg.color(), cs.fg.color());
+            assert!(bg.delta_e(&Color::from_hex(0x2e3440)) <= 3.0);
+            assert!(fg.contrast_ratio(&bg) >= 7.0);
+            assert!(bg.lab().0 <= 30.0);
+        }
+
+        let gruvbox = Collection::new().by_name("Gruvbox Dark").unwrap();
+        assert!(ThemeFilter::Warm.matches(&gruvbox));
+        assert!(!ThemeFilter::Cool.matches(&gruvbox));
+
+        assert!(parse_theme_filters("fg~#ffffff").is_err());
+        assert!(parse_theme_filters("contrast>x").is_err());
+        assert!(parse_theme_filters("sparkly").is_err());
+    }
+
+    #[test]
+    fn json_theme_derives_missing_orange_and_pink() {
+        let nord = Collection::new().by_name("Nord").unwrap().into_theme();
+        let mut value = serde_json::to_value(&nord).unwrap();
+        let colors = value["colors"].as_object_mut().unwrap();
+        for key in ["base", "bright"] {
+            let ansi = colors[key].as_object_mut().unwrap();
+            ansi.remove("orange");
+            ansi.remove("pink");
+        }
+        colors["bright"]["orange"] = "#123456".into();
+
+        let theme = theme_from_json(&value.to_string(), "").unwrap();
+        let c = &theme.colors;
+        assert_eq!(c.base.orange, nord.colors.base.orange);
+        assert_eq!(c.base.pink, nord.colors.base.pink);
+        assert_eq!(c.bright.pink, nord.colors.bright.pink);
+        assert_eq!(c.bright.orange.as_str(), "#123456");
+    }
 }
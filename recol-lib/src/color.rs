@@ -242,6 +242,18 @@ impl Color {
             + 0.0722 * srgb_to_linear(self.b)
     }
 
+    /// Returns the WCAG 2.x contrast ratio against `other`, in `1.0..=21.0`.
+    pub fn contrast_ratio(&self, other: &Color) -> f32 {
+        let (a, b) = (self.luminance(), other.luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Perceptual distance to `other` (CIEDE2000); about 1.0 is a just
+    /// noticeable difference.
+    pub fn delta_e(&self, other: &Color) -> f32 {
+        delta_e2000(self.lab(), other.lab())
+    }
+
     /// Converts a `Color` to CIE L*a*b* (D65 white point).
     pub fn lab(self) -> (f32, f32, f32) {
         let (r, g, b) = (
@@ -258,6 +270,70 @@ impl Color {
         (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
     }
 
+    /// Converts a `Color` to OKLab: `L` in `[0, 1]`, `a`/`b` roughly in
+    /// `[-0.4, 0.4]`.
+    pub fn oklab(self) -> (f32, f32, f32) {
+        let (r, g, b) = (
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b),
+        );
+
+        let l = (0.41222147 * r + 0.53633254 * g + 0.051445993 * b).cbrt();
+        let m = (0.2119035 * r + 0.6806995 * g + 0.10739696 * b).cbrt();
+        let s = (0.08830246 * r + 0.28171884 * g + 0.6299787 * b).cbrt();
+
+        (
+            0.21045426 * l + 0.7936178 * m - 0.004072047 * s,
+            1.9779985 * l - 2.4285922 * m + 0.4505937 * s,
+            0.025904037 * l + 0.78277177 * m - 0.80867577 * s,
+        )
+    }
+
+    /// Converts OKLab back to a `Color`. Out-of-gamut colors keep lightness
+    /// and hue and lose chroma instead of being clipped per channel.
+    pub fn from_oklab(l: f32, a: f32, b: f32) -> Self {
+        let c = a.hypot(b);
+        let h = b.atan2(a).to_degrees();
+        Self::from_oklch(l, c, h)
+    }
+
+    /// Converts a `Color` to OKLCH: lightness `[0, 1]`, chroma `[0, ~0.37]`,
+    /// hue in degrees `[0°, 360°)`.
+    pub fn oklch(self) -> (f32, f32, f32) {
+        let (l, a, b) = self.oklab();
+        (l, a.hypot(b), b.atan2(a).to_degrees().rem_euclid(360.0))
+    }
+
+    /// Converts OKLCH back to a `Color`, reducing chroma until the color fits
+    /// the sRGB gamut.
+    pub fn from_oklch(l: f32, c: f32, h: f32) -> Self {
+        let l = clamp(l, 0.0, 1.0);
+        let (sin, cos) = h.to_radians().sin_cos();
+        let rgb = |c: f32| oklab_to_linear(l, c * cos, c * sin);
+
+        let mut c = c.max(0.0);
+        if !in_gamut(rgb(c)) {
+            let (mut lo, mut hi) = (0.0, c);
+            for _ in 0..20 {
+                let mid = (lo + hi) / 2.0;
+                if in_gamut(rgb(mid)) {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            c = lo;
+        }
+
+        let (r, g, b) = rgb(c);
+        Color::new(
+            linear_to_srgb(r.clamp(0.0, 1.0)),
+            linear_to_srgb(g.clamp(0.0, 1.0)),
+            linear_to_srgb(b.clamp(0.0, 1.0)),
+        )
+    }
+
     /// Linearly interpolates between `self` and `other`.
     ///
     /// `f = 0.0` returns `self`; `f = 1.0` returns `other`.
@@ -269,6 +345,14 @@ impl Color {
         )
     }
 
+    /// Like [`blend`](Self::blend), but interpolates in OKLab, so midpoints
+    /// keep an even perceived lightness instead of turning muddy.
+    pub fn blend_oklab(&self, other: &Color, f: f32) -> Color {
+        let (l1, a1, b1) = self.oklab();
+        let (l2, a2, b2) = other.oklab();
+        Color::from_oklab(l1 + (l2 - l1) * f, a1 + (a2 - a1) * f, b1 + (b2 - b1) * f)
+    }
+
     /// Lightens (`f > 0`) or darkens (`f < 0`) the color by blending toward
     /// white or black by the given proportion `|f|` in `[0.0, 1.0]`.
     pub fn shade(&self, f: f32) -> Color {
@@ -309,9 +393,10 @@ impl Color {
 /// A validated CSS hex color string (e.g. `#1a2b3c`).
 ///
 /// The inner `String` is always a lowercase 7-character string of the form `#rrggbb`.
-/// The only way to construct this type is via [`CssColor::new`] or [`FromStr`],
-/// both of which enforce the invariant.
+/// The only way to construct this type is via [`CssColor::new`], [`FromStr`]
+/// or deserialization, all of which enforce the invariant.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String")]
 pub struct CssColor(String);
 use std::io::Write;
 
@@ -365,6 +450,14 @@ impl Into<String> for CssColor {
     }
 }
 
+impl TryFrom<String> for CssColor {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        Self::new(&s)
+    }
+}
+
 impl std::str::FromStr for CssColor {
     type Err = Error;
 
@@ -383,7 +476,7 @@ const D65_XN: f32 = 0.95047;
 const D65_YN: f32 = 1.00000;
 const D65_ZN: f32 = 1.08883;
 
-fn srgb_to_linear(c: f32) -> f32 {
+pub(crate) fn srgb_to_linear(c: f32) -> f32 {
     if c <= 0.04045 {
         c / 12.92
     } else {
@@ -391,7 +484,7 @@ fn srgb_to_linear(c: f32) -> f32 {
     }
 }
 
-fn linear_to_srgb(c: f32) -> f32 {
+pub(crate) fn linear_to_srgb(c: f32) -> f32 {
     if c <= 0.0031308 {
         c * 12.92
     } else {
@@ -399,6 +492,26 @@ fn linear_to_srgb(c: f32) -> f32 {
     }
 }
 
+/// OKLab to linear sRGB, without any gamut handling.
+fn oklab_to_linear(l: f32, a: f32, b: f32) -> (f32, f32, f32) {
+    let l_ = l + 0.39633778 * a + 0.21580376 * b;
+    let m_ = l - 0.105561346 * a - 0.06385417 * b;
+    let s_ = l - 0.08948418 * a - 1.2914855 * b;
+    let (l, m, s) = (l_ * l_ * l_, m_ * m_ * m_, s_ * s_ * s_);
+
+    (
+        4.0767417 * l - 3.3077116 * m + 0.23096994 * s,
+        -1.268438 * l + 2.6097574 * m - 0.34131938 * s,
+        -0.0041960864 * l - 0.7034186 * m + 1.7076147 * s,
+    )
+}
+
+#[inline]
+fn in_gamut((r, g, b): (f32, f32, f32)) -> bool {
+    const EPS: f32 = 1e-4;
+    [r, g, b].iter().all(|c| (-EPS..=1.0 + EPS).contains(c))
+}
+
 fn lab_f(t: f32) -> f32 {
     if t > 0.008856 {
         t.powf(1.0 / 3.0)
@@ -415,3 +528,99 @@ fn lab_f_inv(t: f32) -> f32 {
         (t - 16.0 / 116.0) / 7.787
     }
 }
+
+/// CIEDE2000 color difference between two CIE L*a*b* colors, with the
+/// parametric factors `kL = kC = kH = 1`.
+pub fn delta_e2000((l1, a1, b1): (f32, f32, f32), (l2, a2, b2): (f32, f32, f32)) -> f32 {
+    const POW25_7: f32 = 6_103_515_625.0; // 25^7
+
+    let c_bar = (a1.hypot(b1) + a2.hypot(b2)) / 2.0;
+    let c_bar7 = c_bar.powi(7);
+    let g = 0.5 * (1.0 - (c_bar7 / (c_bar7 + POW25_7)).sqrt());
+
+    let (a1, a2) = (a1 * (1.0 + g), a2 * (1.0 + g));
+    let (c1, c2) = (a1.hypot(b1), a2.hypot(b2));
+    let hue = |a: f32, b: f32| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+    let (h1, h2) = (hue(a1, b1), hue(a2, b2));
+
+    let dl = l2 - l1;
+    let dc = c2 - c1;
+    let dh = if c1 * c2 == 0.0 {
+        0.0
+    } else if (h2 - h1).abs() <= 180.0 {
+        h2 - h1
+    } else if h2 - h1 > 180.0 {
+        h2 - h1 - 360.0
+    } else {
+        h2 - h1 + 360.0
+    };
+    let dh = 2.0 * (c1 * c2).sqrt() * (dh / 2.0).to_radians().sin();
+
+    let l_bar = (l1 + l2) / 2.0;
+    let c_bar = (c1 + c2) / 2.0;
+    let h_bar = if c1 * c2 == 0.0 {
+        h1 + h2
+    } else if (h1 - h2).abs() <= 180.0 {
+        (h1 + h2) / 2.0
+    } else if h1 + h2 < 360.0 {
+        (h1 + h2 + 360.0) / 2.0
+    } else {
+        (h1 + h2 - 360.0) / 2.0
+    };
+
+    let cos = |deg: f32| deg.to_radians().cos();
+    let t =
+        1.0 - 0.17 * cos(h_bar - 30.0) + 0.24 * cos(2.0 * h_bar) + 0.32 * cos(3.0 * h_bar + 6.0)
+            - 0.20 * cos(4.0 * h_bar - 63.0);
+    let d_theta = 30.0 * (-((h_bar - 275.0) / 25.0).powi(2)).exp();
+    let c_bar7 = c_bar.powi(7);
+    let r_c = 2.0 * (c_bar7 / (c_bar7 + POW25_7)).sqrt();
+    let s_l = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let s_c = 1.0 + 0.045 * c_bar;
+    let s_h = 1.0 + 0.015 * c_bar * t;
+    let r_t = -(2.0 * d_theta).to_radians().sin() * r_c;
+
+    let (dl, dc, dh) = (dl / s_l, dc / s_c, dh / s_h);
+    (dl * dl + dc * dc + dh * dh + r_t * dc * dh)
+        .max(0.0)
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delta_e2000_matches_reference_pairs() {
+        // Sharma, Wu & Dalal (2005) test data.
+        let pairs = [
+            ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+            ((50.0, 0.0, 0.0), (50.0, -1.0, 2.0), 2.3669),
+            ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+            (
+                (60.2574, -34.0099, 36.2677),
+                (60.4626, -34.1751, 39.4387),
+                1.2644,
+            ),
+        ];
+        for (a, b, expected) in pairs {
+            assert!((delta_e2000(a, b) - expected).abs() < 0.001, "{a:?} {b:?}");
+            assert!((delta_e2000(b, a) - expected).abs() < 0.001);
+        }
+        let c = Color::from_hex(0x7aa2f7);
+        assert_eq!(c.delta_e(&c), 0.0);
+    }
+
+    #[test]
+    fn css_color_deserialize_validates() {
+        let c = serde_json::from_str::<CssColor>(r##""#1A2B3C""##).unwrap();
+        assert_eq!(c.as_str(), "#1a2b3c");
+        assert!(serde_json::from_str::<CssColor>(r#""nope""#).is_err());
+    }
+}
@@ -0,0 +1,164 @@
+//! WCAG 2.x contrast audit of a [`ColorScheme`].
+//!
+//! Thresholds for normal-size text:
+//!
+//! ```text
+//! ratio ≥ 7.0   AAA
+//! ratio ≥ 4.5   AA
+//! ratio ≥ 3.0   AA Large  (large or bold text only)
+//! ratio < 3.0   Fail
+//! ```
+
+use crate::{ColorScheme, CssColor};
+use serde::Serialize;
+
+/// Minimum ratio for WCAG AA on normal text.
+pub const WCAG_AA: f32 = 4.5;
+/// Minimum ratio for WCAG AAA on normal text.
+pub const WCAG_AAA: f32 = 7.0;
+/// Minimum ratio for WCAG AA on large text.
+pub const WCAG_AA_LARGE: f32 = 3.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum ContrastLevel {
+    Fail,
+    #[serde(rename = "AA Large")]
+    AaLarge,
+    #[serde(rename = "AA")]
+    Aa,
+    #[serde(rename = "AAA")]
+    Aaa,
+}
+
+impl ContrastLevel {
+    pub fn from_ratio(ratio: f32) -> Self {
+        if ratio >= WCAG_AAA {
+            Self::Aaa
+        } else if ratio >= WCAG_AA {
+            Self::Aa
+        } else if ratio >= WCAG_AA_LARGE {
+            Self::AaLarge
+        } else {
+            Self::Fail
+        }
+    }
+}
+
+impl std::fmt::Display for ContrastLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Fail => "Fail",
+            Self::AaLarge => "AA Large",
+            Self::Aa => "AA",
+            Self::Aaa => "AAA",
+        };
+        f.pad(s)
+    }
+}
+
+/// Contrast of one foreground/background pair.
+#[derive(Clone, Debug, Serialize)]
+pub struct ContrastPair {
+    pub name: &'static str,
+    pub fg: CssColor,
+    pub bg: CssColor,
+    pub ratio: f32,
+    pub level: ContrastLevel,
+}
+
+impl ContrastPair {
+    fn new(name: &'static str, fg: &CssColor, bg: &CssColor) -> Self {
+        let ratio = fg.color().contrast_ratio(&bg.color());
+        Self {
+            name,
+            fg: fg.clone(),
+            bg: bg.clone(),
+            ratio,
+            level: ContrastLevel::from_ratio(ratio),
+        }
+    }
+
+    /// Below AA for normal text.
+    pub fn is_below_aa(&self) -> bool {
+        self.level < ContrastLevel::Aa
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ContrastReport {
+    pub pairs: Vec<ContrastPair>,
+}
+
+impl ContrastReport {
+    /// Pairs below AA for normal text.
+    pub fn failing(&self) -> impl Iterator<Item = &ContrastPair> {
+        self.pairs.iter().filter(|p| p.is_below_aa())
+    }
+}
+
+impl ColorScheme {
+    /// WCAG contrast of fg/bg, selection fg/bg, cursor fg/bg and every ANSI
+    /// color against bg.
+    pub fn contrast_report(&self) -> ContrastReport {
+        let bg = &self.bg;
+        let mut pairs = vec![
+            ContrastPair::new("fg", &self.fg, bg),
+            ContrastPair::new("selection", &self.selection.fg, &self.selection.bg),
+            ContrastPair::new("cursor", &self.cursor.fg, &self.cursor.bg),
+        ];
+
+        const BASE: [&str; 8] = [
+            "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+        ];
+        const BRIGHT: [&str; 8] = [
+            "bright black",
+            "bright red",
+            "bright green",
+            "bright yellow",
+            "bright blue",
+            "bright magenta",
+            "bright cyan",
+            "bright white",
+        ];
+        for (names, a) in [(BASE, &self.base), (BRIGHT, &self.bright)] {
+            let colors = [
+                &a.black, &a.red, &a.green, &a.yellow, &a.blue, &a.magenta, &a.cyan, &a.white,
+            ];
+            for (name, c) in names.into_iter().zip(colors) {
+                pairs.push(ContrastPair::new(name, c, bg));
+            }
+        }
+
+        ContrastReport { pairs }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{COLOR_SCHEME_NC, Color};
+
+    #[test]
+    fn black_on_white_is_21() {
+        let ratio = Color::from_hex(0x000000).contrast_ratio(&Color::from_hex(0xffffff));
+        assert!((ratio - 21.0).abs() < 0.01);
+        assert_eq!(ContrastLevel::from_ratio(ratio), ContrastLevel::Aaa);
+        assert_eq!(ContrastLevel::from_ratio(4.5), ContrastLevel::Aa);
+        assert_eq!(ContrastLevel::from_ratio(3.2), ContrastLevel::AaLarge);
+        assert_eq!(ContrastLevel::from_ratio(1.0), ContrastLevel::Fail);
+    }
+
+    #[test]
+    fn report_flags_dark_blue_on_black() {
+        let mut colors = [Color::from_hex(0xcccccc); COLOR_SCHEME_NC];
+        colors[0] = Color::from_hex(0x000000);
+        colors[10] = Color::from_hex(0x0000aa);
+        let report = ColorScheme::from_color_slice(&colors).contrast_report();
+
+        assert_eq!(report.pairs.len(), 19);
+        let blue = report.pairs.iter().find(|p| p.name == "blue").unwrap();
+        assert_eq!(blue.level, ContrastLevel::Fail);
+        assert!(report.failing().any(|p| p.name == "blue"));
+        assert!(!report.failing().any(|p| p.name == "fg"));
+    }
+}
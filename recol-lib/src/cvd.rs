@@ -0,0 +1,180 @@
+//! Color vision deficiency (CVD) simulation and daltonization.
+//!
+//! Simulation works in linear RGB with the matrices from DaltonLens:
+//!
+//! ```text
+//! protan, deutan   Viénot et al. 1999, one projection plane
+//! tritan           Brettel et al. 1997, two half-planes split by a
+//!                  plane through white
+//! ```
+//!
+//! Daltonization (Fidaner et al.) takes the information lost in simulation
+//! and moves it onto channels the viewer can still see.
+
+use crate::Color;
+use crate::color::{linear_to_srgb, srgb_to_linear};
+
+type Matrix = [[f32; 3]; 3];
+
+const PROTAN: Matrix = [
+    [0.11238, 0.88762, 0.00000],
+    [0.11238, 0.88762, 0.00000],
+    [0.00401, -0.00401, 1.00000],
+];
+
+const DEUTAN: Matrix = [
+    [0.29275, 0.70725, 0.00000],
+    [0.29275, 0.70725, 0.00000],
+    [-0.02234, 0.02234, 1.00000],
+];
+
+const TRITAN_1: Matrix = [
+    [1.01277, 0.13548, -0.14826],
+    [-0.01243, 0.86812, 0.14431],
+    [0.07589, 0.80500, 0.11911],
+];
+
+const TRITAN_2: Matrix = [
+    [0.93678, 0.18979, -0.12657],
+    [0.06154, 0.81526, 0.12320],
+    [-0.37562, 1.12767, 0.24796],
+];
+
+/// Normal of the plane choosing between [`TRITAN_1`] and [`TRITAN_2`].
+const TRITAN_SEPARATION: [f32; 3] = [0.03901, -0.02788, -0.01113];
+
+/// Spreads the red-green error onto green and blue.
+const DALTONIZE_RED_GREEN: Matrix = [[0.0, 0.0, 0.0], [0.7, 1.0, 0.0], [0.7, 0.0, 1.0]];
+
+/// Spreads the blue-yellow error onto red and green.
+const DALTONIZE_BLUE_YELLOW: Matrix = [[1.0, 0.0, 0.7], [0.0, 1.0, 0.7], [0.0, 0.0, 0.0]];
+
+/// Type of color vision deficiency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorVisionDeficiency {
+    /// Missing or anomalous L cones (red).
+    Protan,
+    /// Missing or anomalous M cones (green); the most common type.
+    Deutan,
+    /// Missing or anomalous S cones (blue).
+    Tritan,
+}
+
+impl std::str::FromStr for ColorVisionDeficiency {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "p" | "protan" | "protanopia" | "protanomaly" => Ok(Self::Protan),
+            "d" | "deutan" | "deuteranopia" | "deuteranomaly" => Ok(Self::Deutan),
+            "t" | "tritan" | "tritanopia" | "tritanomaly" => Ok(Self::Tritan),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for ColorVisionDeficiency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Protan => write!(f, "protan"),
+            Self::Deutan => write!(f, "deutan"),
+            Self::Tritan => write!(f, "tritan"),
+        }
+    }
+}
+
+fn mul(m: &Matrix, v: [f32; 3]) -> [f32; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+fn to_linear(c: &Color) -> [f32; 3] {
+    [c.r, c.g, c.b].map(srgb_to_linear)
+}
+
+fn from_linear(v: [f32; 3]) -> Color {
+    let [r, g, b] = v.map(|c| linear_to_srgb(c.clamp(0.0, 1.0)));
+    Color::new(r, g, b)
+}
+
+impl ColorVisionDeficiency {
+    /// Full-severity simulation in linear RGB.
+    fn simulate_linear(&self, rgb: [f32; 3]) -> [f32; 3] {
+        match self {
+            Self::Protan => mul(&PROTAN, rgb),
+            Self::Deutan => mul(&DEUTAN, rgb),
+            Self::Tritan => {
+                let n = TRITAN_SEPARATION;
+                if rgb[0] * n[0] + rgb[1] * n[1] + rgb[2] * n[2] >= 0.0 {
+                    mul(&TRITAN_1, rgb)
+                } else {
+                    mul(&TRITAN_2, rgb)
+                }
+            }
+        }
+    }
+}
+
+impl Color {
+    /// How the color looks with `kind` of CVD. `severity` in `[0.0, 1.0]`
+    /// blends from normal vision (`0.0`) to dichromacy (`1.0`), which
+    /// approximates the anomalous trichromacies in between.
+    pub fn simulate_cvd(&self, kind: ColorVisionDeficiency, severity: f32) -> Color {
+        let t = severity.clamp(0.0, 1.0);
+        let rgb = to_linear(self);
+        let sim = kind.simulate_linear(rgb);
+        from_linear([0, 1, 2].map(|i| rgb[i] + (sim[i] - rgb[i]) * t))
+    }
+
+    /// Shifts the color so that what `kind` of CVD hides shows up on the
+    /// remaining channels. `strength` in `[0.0, 1.0]`; neutral colors are
+    /// left as they are.
+    pub fn daltonize(&self, kind: ColorVisionDeficiency, strength: f32) -> Color {
+        let t = strength.clamp(0.0, 1.0);
+        let rgb = to_linear(self);
+        let sim = kind.simulate_linear(rgb);
+        let error = [0, 1, 2].map(|i| rgb[i] - sim[i]);
+        let shift = match kind {
+            ColorVisionDeficiency::Protan | ColorVisionDeficiency::Deutan => {
+                mul(&DALTONIZE_RED_GREEN, error)
+            }
+            ColorVisionDeficiency::Tritan => mul(&DALTONIZE_BLUE_YELLOW, error),
+        };
+        from_linear([0, 1, 2].map(|i| rgb[i] + shift[i] * t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delta_e(a: &Color, b: &Color) -> f32 {
+        let (a, b) = (a.lab(), b.lab());
+        ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2)).sqrt()
+    }
+
+    #[test]
+    fn simulation_keeps_neutrals_and_merges_red_green() {
+        use ColorVisionDeficiency::*;
+        for kind in [Protan, Deutan, Tritan] {
+            for gray in [0x000000, 0x808080, 0xffffff] {
+                let c = Color::from_hex(gray);
+                assert!(delta_e(&c.simulate_cvd(kind, 1.0), &c) < 1.0, "{kind} {c}");
+                assert_eq!(c.daltonize(kind, 1.0).css(), c.css());
+            }
+        }
+
+        let red = Color::from_hex(0xcc241d);
+        let green = Color::from_hex(0x98971a);
+        assert_eq!(red.simulate_cvd(Deutan, 0.0).css(), red.css());
+        let before = delta_e(
+            &red.simulate_cvd(Deutan, 1.0),
+            &green.simulate_cvd(Deutan, 1.0),
+        );
+        let after = delta_e(
+            &red.daltonize(Deutan, 1.0).simulate_cvd(Deutan, 1.0),
+            &green.daltonize(Deutan, 1.0).simulate_cvd(Deutan, 1.0),
+        );
+        assert!(before < 15.0, "{before}");
+        assert!(after > before * 1.5, "{before} -> {after}");
+    }
+}
@@ -0,0 +1,295 @@
+//! Theme extraction from image pixels.
+//!
+//! Pixels are clustered with k-means in CIE Lab, then:
+//!
+//! ```text
+//! bg / fg     darkest / lightest cluster (swapped for light images),
+//!             pushed to readable lightness with reduced chroma
+//! black/white bg / fg shifted toward the middle
+//! red … cyan  chromatic cluster closest in hue to each slot, moved to a
+//!             common lightness; missing hues fall back to the slot hue
+//! cursor      most colorful cluster
+//! ```
+
+use crate::{COLOR_SCHEME_NC, Color, ColorScheme, Theme};
+
+/// Clusters computed per image.
+const K: usize = 16;
+const MAX_ITERATIONS: usize = 20;
+/// Pixels beyond this are subsampled with a fixed stride.
+const MAX_SAMPLES: usize = 8192;
+
+/// Lab hues of red, green, yellow, blue, magenta and cyan, in ANSI order.
+const ANSI_HUES: [f32; 6] = [35.0, 140.0, 95.0, 285.0, 330.0, 200.0];
+/// A cluster further than this from a slot's hue is not used for it.
+const MAX_HUE_DISTANCE: f32 = 50.0;
+/// Clusters below this Lab chroma count as neutral.
+const MIN_CHROMA: f32 = 12.0;
+
+/// Lab lightness levels for one theme polarity. `bg` and `fg` are limits:
+/// the source clusters are only moved if they are not already beyond them.
+struct Levels {
+    bg: f32,
+    fg: f32,
+    black: [f32; 2],
+    white: [f32; 2],
+    color: [f32; 2],
+}
+
+const DARK: Levels = Levels {
+    bg: 16.0,
+    fg: 86.0,
+    black: [28.0, 48.0],
+    white: [78.0, 94.0],
+    color: [68.0, 76.0],
+};
+
+const LIGHT: Levels = Levels {
+    bg: 94.0,
+    fg: 30.0,
+    black: [30.0, 45.0],
+    white: [82.0, 90.0],
+    color: [48.0, 54.0],
+};
+
+#[derive(Clone, Copy)]
+struct Cluster {
+    lab: [f32; 3],
+    weight: f32,
+}
+
+impl Cluster {
+    fn chroma(&self) -> f32 {
+        self.lab[1].hypot(self.lab[2])
+    }
+
+    fn hue(&self) -> f32 {
+        self.lab[2]
+            .atan2(self.lab[1])
+            .to_degrees()
+            .rem_euclid(360.0)
+    }
+}
+
+fn distance2(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+fn hue_distance(a: f32, b: f32) -> f32 {
+    let d = (a - b).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+
+/// Deterministic k-means: seeds are picked farthest-first so the same image
+/// always gives the same theme.
+fn kmeans(samples: &[[f32; 3]], k: usize) -> Vec<Cluster> {
+    let mut centers = vec![samples[0]];
+    let mut nearest = samples
+        .iter()
+        .map(|s| distance2(s, &samples[0]))
+        .collect::<Vec<_>>();
+    while centers.len() < k {
+        let (i, &d) = nearest
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap_or((0, &0.0));
+        if d <= f32::EPSILON {
+            break;
+        }
+        centers.push(samples[i]);
+        for (n, s) in nearest.iter_mut().zip(samples) {
+            *n = n.min(distance2(s, &samples[i]));
+        }
+    }
+
+    let mut assignment = vec![0usize; samples.len()];
+    for _ in 0..MAX_ITERATIONS {
+        let mut changed = false;
+        for (a, s) in assignment.iter_mut().zip(samples) {
+            let best = (0..centers.len())
+                .min_by(|&x, &y| distance2(s, &centers[x]).total_cmp(&distance2(s, &centers[y])))
+                .unwrap_or(0);
+            changed |= *a != best;
+            *a = best;
+        }
+
+        let mut sums = vec![([0.0f32; 3], 0usize); centers.len()];
+        for (&a, s) in assignment.iter().zip(samples) {
+            for (sum, v) in sums[a].0.iter_mut().zip(s) {
+                *sum += v;
+            }
+            sums[a].1 += 1;
+        }
+        for (center, (sum, n)) in centers.iter_mut().zip(&sums) {
+            if *n > 0 {
+                *center = sum.map(|v| v / *n as f32);
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut counts = vec![0usize; centers.len()];
+    for &a in &assignment {
+        counts[a] += 1;
+    }
+    centers
+        .into_iter()
+        .zip(counts)
+        .filter(|&(_, n)| n > 0)
+        .map(|(lab, n)| Cluster {
+            lab,
+            weight: n as f32 / samples.len() as f32,
+        })
+        .collect()
+}
+
+/// Lab color at lightness `l` with the hue of `(a, b)` and its chroma scaled
+/// by `chroma`.
+fn with_lightness(l: f32, [_, a, b]: [f32; 3], chroma: f32) -> Color {
+    Color::from_lab(l, a * chroma, b * chroma)
+}
+
+impl Theme {
+    /// Builds a theme from the colors of an image. `is_light` forces the
+    /// polarity; by default it follows the image's average lightness.
+    /// Returns `None` for an empty image.
+    pub fn from_pixels(
+        pixels: &[Color],
+        name: impl Into<String>,
+        is_light: Option<bool>,
+    ) -> Option<Self> {
+        if pixels.is_empty() {
+            return None;
+        }
+        let stride = pixels.len().div_ceil(MAX_SAMPLES);
+        let samples = pixels
+            .iter()
+            .step_by(stride)
+            .map(|c| {
+                let (l, a, b) = c.lab();
+                [l, a, b]
+            })
+            .collect::<Vec<_>>();
+
+        let mut clusters = kmeans(&samples, K);
+        clusters.sort_by(|a, b| a.lab[0].total_cmp(&b.lab[0]));
+
+        let mean_l = clusters.iter().map(|c| c.lab[0] * c.weight).sum::<f32>();
+        let is_light = is_light.unwrap_or(mean_l > 60.0);
+
+        let (darkest, lightest) = (clusters[0], clusters[clusters.len() - 1]);
+        let (bg_src, fg_src) = if is_light {
+            (lightest, darkest)
+        } else {
+            (darkest, lightest)
+        };
+        let lv = if is_light { LIGHT } else { DARK };
+        let bg_l = if is_light {
+            bg_src.lab[0].max(lv.bg)
+        } else {
+            bg_src.lab[0].min(lv.bg)
+        };
+        let fg_l = if is_light {
+            fg_src.lab[0].min(lv.fg)
+        } else {
+            fg_src.lab[0].max(lv.fg)
+        };
+
+        let bg = with_lightness(bg_l, bg_src.lab, 0.5);
+        let fg = with_lightness(fg_l, fg_src.lab, 0.3);
+
+        let chromatic = clusters
+            .iter()
+            .filter(|c| c.chroma() >= MIN_CHROMA)
+            .collect::<Vec<_>>();
+        let mean_chroma = if chromatic.is_empty() {
+            40.0
+        } else {
+            (chromatic.iter().map(|c| c.chroma()).sum::<f32>() / chromatic.len() as f32).max(30.0)
+        };
+        let slot = |hue: f32| -> [f32; 3] {
+            chromatic
+                .iter()
+                .filter(|c| hue_distance(c.hue(), hue) <= MAX_HUE_DISTANCE)
+                // Prefer close hues, then colorful and frequent clusters.
+                .min_by(|x, y| {
+                    let score = |c: &Cluster| {
+                        hue_distance(c.hue(), hue) - c.chroma() * 0.5 - c.weight * 50.0
+                    };
+                    score(x).total_cmp(&score(y))
+                })
+                .map(|c| {
+                    let chroma = c.chroma().max(30.0);
+                    let (sin, cos) = c.hue().to_radians().sin_cos();
+                    [c.lab[0], chroma * cos, chroma * sin]
+                })
+                .unwrap_or_else(|| {
+                    let (sin, cos) = hue.to_radians().sin_cos();
+                    [0.0, mean_chroma * cos, mean_chroma * sin]
+                })
+        };
+        let hues = ANSI_HUES.map(slot);
+
+        let accent = clusters
+            .iter()
+            .max_by(|a, b| a.chroma().total_cmp(&b.chroma()))
+            .map(|c| with_lightness(lv.color[0], c.lab, 1.0))
+            .unwrap_or(fg);
+
+        let mut c = [Color::default(); COLOR_SCHEME_NC];
+        c[0] = bg;
+        c[1] = fg;
+        c[2] = bg.blend(&fg, 0.25);
+        c[3] = fg;
+        c[4] = accent;
+        c[5] = bg;
+        for (bright, offset) in [(0, 6), (1, 14)] {
+            c[offset] = with_lightness(lv.black[bright], bg_src.lab, 0.5);
+            for (i, lab) in hues.iter().enumerate() {
+                c[offset + 1 + i] = with_lightness(lv.color[bright], *lab, 1.0);
+            }
+            c[offset + 7] = with_lightness(lv.white[bright], fg_src.lab, 0.3);
+        }
+
+        Some(Theme::new(
+            name,
+            is_light,
+            ColorScheme::from_color_slice(&c),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_polarity_and_hues_from_pixels() {
+        // Mostly dark navy with patches of orange-red and teal.
+        let mut pixels = vec![Color::from_hex(0x14182a); 900];
+        pixels.extend(vec![Color::from_hex(0xe0603a); 60]);
+        pixels.extend(vec![Color::from_hex(0x2aa198); 60]);
+        pixels.extend(vec![Color::from_hex(0xe8e4d8); 40]);
+
+        let theme = Theme::from_pixels(&pixels, "wall", None).unwrap();
+        let cs = &theme.colors;
+        assert!(!theme.is_light);
+        assert!(cs.bg.color().lab().0 <= 16.5);
+        assert!(cs.fg.color().contrast_ratio(&cs.bg.color()) >= 7.0);
+
+        let (r, g, b) = cs.base.red.color().rgb();
+        assert!(r > g && r > b);
+        let (r, g, b) = cs.base.cyan.color().rgb();
+        assert!(g > r && b > r);
+
+        assert!(
+            Theme::from_pixels(&pixels, "wall", Some(true))
+                .unwrap()
+                .is_light
+        );
+        assert!(Theme::from_pixels(&[], "empty", None).is_none());
+    }
+}
@@ -0,0 +1,167 @@
+//! Theme generation from a single accent color.
+//!
+//! Everything is laid out in OKLCH so the palette has even perceived
+//! lightness:
+//!
+//! ```text
+//! bg, fg, black, white   accent hue, low chroma
+//! selection              bg moved toward fg, tinted with the accent
+//! cursor                 accent, with bg or fg as text, whichever reads better
+//! red … cyan             canonical hues; the slot closest to the accent
+//!                        takes the accent hue, all share one chroma
+//! ```
+
+use crate::{COLOR_SCHEME_NC, Color, ColorScheme, Theme};
+
+/// OKLCH hues of red, green, yellow, blue, magenta and cyan, in ANSI order.
+const ANSI_HUES: [f32; 6] = [25.0, 145.0, 95.0, 260.0, 330.0, 200.0];
+
+/// An accent within this many degrees replaces the closest ANSI hue.
+const ACCENT_SNAP: f32 = 30.0;
+
+/// Tuning for [`Theme::from_seed`].
+#[derive(Debug, Clone)]
+pub struct SeedOptions {
+    /// Theme name; defaults to `Seed #rrggbb`.
+    pub name: Option<String>,
+    /// OKLCH chroma of the ANSI colors; defaults to the accent's chroma,
+    /// limited to `0.08..=0.16`.
+    pub chroma: Option<f32>,
+    /// OKLCH chroma of bg/fg/black/white, i.e. how much of the accent hue
+    /// tints the neutrals.
+    pub tint: f32,
+}
+
+impl Default for SeedOptions {
+    fn default() -> Self {
+        Self {
+            name: None,
+            chroma: None,
+            tint: 0.02,
+        }
+    }
+}
+
+/// Lightness levels for one theme polarity.
+struct Levels {
+    bg: f32,
+    fg: f32,
+    selection: f32,
+    black: [f32; 2],
+    white: [f32; 2],
+    color: [f32; 2],
+}
+
+const DARK: Levels = Levels {
+    bg: 0.22,
+    fg: 0.88,
+    selection: 0.34,
+    black: [0.32, 0.50],
+    white: [0.80, 0.95],
+    color: [0.72, 0.80],
+};
+
+const LIGHT: Levels = Levels {
+    bg: 0.97,
+    fg: 0.30,
+    selection: 0.86,
+    black: [0.30, 0.45],
+    white: [0.80, 0.90],
+    color: [0.48, 0.53],
+};
+
+fn hue_distance(a: f32, b: f32) -> f32 {
+    let d = (a - b).rem_euclid(360.0);
+    d.min(360.0 - d)
+}
+
+impl Theme {
+    /// Builds a complete theme around `accent`: neutrals tinted with its hue,
+    /// the accent as cursor, and six ANSI colors at canonical hues with
+    /// matching lightness and chroma.
+    pub fn from_seed(accent: Color, is_light: bool, options: SeedOptions) -> Self {
+        let (_, accent_c, accent_h) = accent.oklch();
+        let chroma = options.chroma.unwrap_or_else(|| accent_c.clamp(0.08, 0.16));
+        let tint = options.tint;
+        let lv = if is_light { LIGHT } else { DARK };
+        let neutral = |l: f32| Color::from_oklch(l, tint, accent_h);
+
+        let snap = ANSI_HUES
+            .iter()
+            .enumerate()
+            .map(|(i, &h)| (i, hue_distance(h, accent_h)))
+            .filter(|&(_, d)| d <= ACCENT_SNAP)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i);
+        let hues = std::array::from_fn::<f32, 6, _>(|i| {
+            if snap == Some(i) {
+                accent_h
+            } else {
+                ANSI_HUES[i]
+            }
+        });
+
+        let bg = neutral(lv.bg);
+        let fg = neutral(lv.fg);
+
+        let mut c = [Color::default(); COLOR_SCHEME_NC];
+        c[0] = bg;
+        c[1] = fg;
+        c[2] = Color::from_oklch(lv.selection, (accent_c * 0.4).min(0.06), accent_h);
+        c[3] = fg;
+        c[4] = accent;
+        c[5] = if accent.contrast_ratio(&bg) >= accent.contrast_ratio(&fg) {
+            bg
+        } else {
+            fg
+        };
+        for (bright, offset) in [(0, 6), (1, 14)] {
+            c[offset] = neutral(lv.black[bright]);
+            for (i, &h) in hues.iter().enumerate() {
+                c[offset + 1 + i] = Color::from_oklch(lv.color[bright], chroma, h);
+            }
+            c[offset + 7] = neutral(lv.white[bright]);
+        }
+
+        let name = options
+            .name
+            .unwrap_or_else(|| format!("Seed {}", accent.css()));
+        Theme::new(name, is_light, ColorScheme::from_color_slice(&c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_palette_is_readable_and_hue_correct() {
+        let accent = Color::from_hex(0x7aa2f7);
+        for is_light in [false, true] {
+            let theme = Theme::from_seed(accent, is_light, SeedOptions::default());
+            let cs = &theme.colors;
+            assert_eq!(theme.is_light, is_light);
+            assert_eq!(cs.cursor.bg, accent.css());
+
+            let bg = cs.bg.color();
+            assert!(cs.fg.color().contrast_ratio(&bg) >= 7.0);
+            for c in [&cs.base.red, &cs.base.green, &cs.base.yellow, &cs.base.blue] {
+                assert!(c.color().contrast_ratio(&bg) >= 4.5, "{c} on {}", cs.bg);
+            }
+
+            let (r, g, b) = cs.base.red.color().rgb();
+            assert!(r > g && r > b);
+            let (r, g, b) = cs.base.green.color().rgb();
+            assert!(g > r && g > b);
+            let (r, g, b) = cs.base.yellow.color().rgb();
+            assert!(r > b && g > b);
+        }
+
+        let blue = Theme::from_seed(accent, false, SeedOptions::default())
+            .colors
+            .base
+            .blue
+            .color();
+        assert!((blue.oklch().2 - accent.oklch().2).abs() < 2.0);
+    }
+}
@@ -0,0 +1,646 @@
+//! Importers that turn color schemes from other terminals and editors into a
+//! [`Theme`].
+//!
+//! Every parser fills the canonical color array (see
+//! [`ColorScheme::from_color_slice`]) and derives whatever the source format
+//! does not define:
+//!
+//! ```text
+//! missing bright color     → normal color
+//! missing selection bg/fg  → blend(bg, fg, 25 %) / fg
+//! missing cursor bg/fg     → fg / bg
+//! ```
+
+use crate::{COLOR_SCHEME_NC, Color, ColorScheme, Theme, parse_json_theme};
+use std::{io, path::Path};
+
+/// Source formats understood by [`import_theme`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportFormat {
+    Ghostty,
+    Alacritty,
+    Kitty,
+    Iterm2,
+    WindowsTerminal,
+    Xresources,
+    Base16,
+    /// recol JSON (`--json` output or a serialized [`Theme`]).
+    Recol,
+}
+
+impl std::str::FromStr for ImportFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ghostty" => Ok(Self::Ghostty),
+            "alacritty" => Ok(Self::Alacritty),
+            "kitty" => Ok(Self::Kitty),
+            "iterm" | "iterm2" | "itermcolors" => Ok(Self::Iterm2),
+            "wt" | "windows-terminal" => Ok(Self::WindowsTerminal),
+            "xresources" | "xrdb" => Ok(Self::Xresources),
+            "base16" => Ok(Self::Base16),
+            "recol" | "json" => Ok(Self::Recol),
+            _ => Err(()),
+        }
+    }
+}
+
+impl ImportFormat {
+    /// Guess the format from the file name, then from the content.
+    pub fn detect(path: &Path, content: &str) -> Self {
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match ext.as_str() {
+            "itermcolors" => return Self::Iterm2,
+            "toml" => return Self::Alacritty,
+            "yaml" | "yml" => return Self::Base16,
+            "conf" if !content.contains('=') => return Self::Kitty,
+            "json" => {
+                return if content.contains("\"colors\"") {
+                    Self::Recol
+                } else {
+                    Self::WindowsTerminal
+                };
+            }
+            _ => {}
+        }
+        if file_name.contains("xresources") || file_name.contains("xdefaults") {
+            return Self::Xresources;
+        }
+
+        let trimmed = content.trim_start();
+        if trimmed.starts_with("<?xml") || trimmed.starts_with("<plist") {
+            Self::Iterm2
+        } else if trimmed.starts_with('{') {
+            Self::WindowsTerminal
+        } else if content.contains("base00") {
+            Self::Base16
+        } else if content.contains("palette") && content.contains('=') {
+            Self::Ghostty
+        } else if content.contains("[colors") {
+            Self::Alacritty
+        } else if content.contains("*color") || content.contains(".color0") {
+            Self::Xresources
+        } else {
+            Self::Kitty
+        }
+    }
+}
+
+/// Read `path` and convert it to a [`Theme`], detecting the format unless
+/// `format` is given. The theme is named after the scheme name found in the
+/// file, or the file stem.
+pub fn import_theme(path: impl AsRef<Path>, format: Option<ImportFormat>) -> io::Result<Theme> {
+    let path = path.as_ref();
+    let name = path
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or("Imported")
+        .trim_start_matches('.')
+        .to_string();
+    let content = std::fs::read_to_string(path)?;
+
+    match format.unwrap_or_else(|| ImportFormat::detect(path, &content)) {
+        ImportFormat::Ghostty => crate::parse_ghostty_theme(path, &name),
+        ImportFormat::Recol => parse_json_theme(path, &name),
+        ImportFormat::Alacritty => parse_alacritty(&content, &name),
+        ImportFormat::Kitty => parse_kitty(&content, &name),
+        ImportFormat::Iterm2 => parse_iterm2(&content, &name),
+        ImportFormat::WindowsTerminal => parse_windows_terminal(&content, &name),
+        ImportFormat::Xresources => parse_xresources(&content, &name),
+        ImportFormat::Base16 => parse_base16(&content, &name),
+    }
+}
+
+/// Parse a color value in any of the notations used by the supported
+/// formats: `#rgb`, `#rrggbb`, `0xrrggbb`, bare `rrggbb` and X11
+/// `rgb:r/g/b` with 1–4 hex digits per channel.
+pub fn parse_color_value(s: &str) -> Option<Color> {
+    let s = s.trim().trim_matches(|c| c == '"' || c == '\'');
+
+    if let Some(rgb) = s.strip_prefix("rgb:") {
+        let mut channels = rgb.split('/').map(|c| {
+            let max = 16u32.checked_pow(c.len() as u32)? - 1;
+            (1..=4)
+                .contains(&c.len())
+                .then(|| u32::from_str_radix(c, 16).ok())
+                .flatten()
+                .map(|v| (v as f32 / max as f32 * 255.0).round() as u8)
+        });
+        let (r, g, b) = (channels.next()??, channels.next()??, channels.next()??);
+        return channels.next().is_none().then(|| Color::from_rgb(r, g, b));
+    }
+
+    let hex = s
+        .strip_prefix('#')
+        .or_else(|| s.strip_prefix("0x"))
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s);
+    match hex.len() {
+        3 => {
+            let expanded: String = hex.chars().flat_map(|c| [c, c]).collect();
+            expanded.parse().ok()
+        }
+        6 => hex.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Canonical color slots, filled in by the individual parsers.
+#[derive(Default)]
+struct Slots([Option<Color>; COLOR_SCHEME_NC]);
+
+impl Slots {
+    const BG: usize = 0;
+    const FG: usize = 1;
+    const SEL_BG: usize = 2;
+    const SEL_FG: usize = 3;
+    const CUR_BG: usize = 4;
+    const CUR_FG: usize = 5;
+    const ANSI: usize = 6;
+
+    /// Set ANSI palette entry `i` (`0..16`); out-of-range indices are ignored.
+    fn set_ansi(&mut self, i: usize, c: Option<Color>) {
+        if i < 16 {
+            self.set(Self::ANSI + i, c);
+        }
+    }
+
+    fn set(&mut self, slot: usize, c: Option<Color>) {
+        if c.is_some() {
+            self.0[slot] = c;
+        }
+    }
+
+    fn into_theme(self, name: &str) -> io::Result<Theme> {
+        let s = self.0;
+        let (Some(bg), Some(fg)) = (s[Self::BG], s[Self::FG]) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{name}: missing background or foreground"),
+            ));
+        };
+
+        let mut colors = [Color::default(); COLOR_SCHEME_NC];
+        colors[Self::BG] = bg;
+        colors[Self::FG] = fg;
+        colors[Self::SEL_BG] = s[Self::SEL_BG].unwrap_or_else(|| bg.blend(&fg, 0.25));
+        colors[Self::SEL_FG] = s[Self::SEL_FG].unwrap_or(fg);
+        colors[Self::CUR_BG] = s[Self::CUR_BG].unwrap_or(fg);
+        colors[Self::CUR_FG] = s[Self::CUR_FG].unwrap_or(bg);
+        for i in 0..8 {
+            let normal = s[Self::ANSI + i].or(s[Self::ANSI + 8 + i]).unwrap_or(fg);
+            colors[Self::ANSI + i] = normal;
+            colors[Self::ANSI + 8 + i] = s[Self::ANSI + 8 + i].unwrap_or(normal);
+        }
+
+        let scheme = ColorScheme::from_color_slice(&colors);
+        let is_light = bg.hsl().2 > 50.0;
+        Ok(Theme::new(name, is_light, scheme))
+    }
+}
+
+fn invalid_data(err: impl std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+const ANSI_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// Alacritty TOML: `[colors.primary]`, `[colors.normal]`, `[colors.bright]`,
+/// `[colors.cursor]`, `[colors.selection]`.
+pub fn parse_alacritty(content: &str, name: &str) -> io::Result<Theme> {
+    let value = content.parse::<toml::Table>().map_err(invalid_data)?;
+    let colors = value
+        .get("colors")
+        .ok_or_else(|| invalid_data(format!("{name}: missing [colors]")))?;
+    let get = |section: &str, key: &str| {
+        colors
+            .get(section)?
+            .get(key)?
+            .as_str()
+            .and_then(parse_color_value)
+    };
+
+    let mut s = Slots::default();
+    s.set(Slots::BG, get("primary", "background"));
+    s.set(Slots::FG, get("primary", "foreground"));
+    s.set(Slots::SEL_BG, get("selection", "background"));
+    s.set(Slots::SEL_FG, get("selection", "text"));
+    s.set(Slots::CUR_BG, get("cursor", "cursor"));
+    s.set(Slots::CUR_FG, get("cursor", "text"));
+    for (i, ansi) in ANSI_NAMES.iter().enumerate() {
+        s.set_ansi(i, get("normal", ansi));
+        s.set_ansi(8 + i, get("bright", ansi));
+    }
+
+    s.into_theme(name)
+}
+
+/// kitty `.conf`: `background #rrggbb`, `color0`..`color15`, etc.
+pub fn parse_kitty(content: &str, name: &str) -> io::Result<Theme> {
+    let mut s = Slots::default();
+    let mut theme_name = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(n) = line.strip_prefix("## name:") {
+            theme_name = Some(n.trim().to_string());
+            continue;
+        }
+        let Some((key, value)) = line.split_once(char::is_whitespace) else {
+            continue;
+        };
+        let c = parse_color_value(value);
+        match key {
+            "background" => s.set(Slots::BG, c),
+            "foreground" => s.set(Slots::FG, c),
+            "selection_background" => s.set(Slots::SEL_BG, c),
+            "selection_foreground" => s.set(Slots::SEL_FG, c),
+            "cursor" => s.set(Slots::CUR_BG, c),
+            "cursor_text_color" => s.set(Slots::CUR_FG, c),
+            _ => {
+                if let Some(i) = key.strip_prefix("color").and_then(|i| i.parse().ok()) {
+                    s.set_ansi(i, c);
+                }
+            }
+        }
+    }
+
+    s.into_theme(theme_name.as_deref().unwrap_or(name))
+}
+
+/// iTerm2 `.itermcolors` property list (XML).
+pub fn parse_iterm2(content: &str, name: &str) -> io::Result<Theme> {
+    let mut s = Slots::default();
+
+    // Flat walk over the plist: a top-level `<key>` names the color entry,
+    // and the nested `<dict>` holds `<key>… Component</key><real>…</real>`.
+    let mut entry: Option<String> = None;
+    let mut component: Option<String> = None;
+    let mut rgb = [None::<f32>; 3];
+    let mut depth = 0usize;
+    let mut rest = content;
+
+    while let Some(open) = rest.find('<') {
+        let Some(close) = rest[open..].find('>') else {
+            break;
+        };
+        let tag = &rest[open + 1..open + close];
+        rest = &rest[open + close + 1..];
+        let text_end = rest.find('<').unwrap_or(rest.len());
+        let text = rest[..text_end].trim();
+
+        match tag {
+            "dict" => depth += 1,
+            "/dict" => {
+                depth = depth.saturating_sub(1);
+                if depth == 1 {
+                    if let (Some(key), [Some(r), Some(g), Some(b)]) = (entry.take(), rgb) {
+                        let c = Some(Color::new(r, g, b));
+                        match key.as_str() {
+                            "Background Color" => s.set(Slots::BG, c),
+                            "Foreground Color" => s.set(Slots::FG, c),
+                            "Selection Color" => s.set(Slots::SEL_BG, c),
+                            "Selected Text Color" => s.set(Slots::SEL_FG, c),
+                            "Cursor Color" => s.set(Slots::CUR_BG, c),
+                            "Cursor Text Color" => s.set(Slots::CUR_FG, c),
+                            _ => {
+                                if let Some(i) = key
+                                    .strip_prefix("Ansi ")
+                                    .and_then(|k| k.strip_suffix(" Color"))
+                                    .and_then(|i| i.parse().ok())
+                                {
+                                    s.set_ansi(i, c);
+                                }
+                            }
+                        }
+                    }
+                    rgb = [None; 3];
+                }
+            }
+            "key" if depth == 1 => entry = Some(text.to_string()),
+            "key" => component = Some(text.to_string()),
+            "real" | "integer" => {
+                let v = text.parse::<f32>().ok();
+                match component.take().as_deref() {
+                    Some("Red Component") => rgb[0] = v,
+                    Some("Green Component") => rgb[1] = v,
+                    Some("Blue Component") => rgb[2] = v,
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    s.into_theme(name)
+}
+
+/// Windows Terminal color scheme JSON: a single scheme object, or a
+/// `settings.json` with a `schemes` array (the first scheme is used).
+pub fn parse_windows_terminal(content: &str, name: &str) -> io::Result<Theme> {
+    let value = serde_json::from_str::<serde_json::Value>(content).map_err(invalid_data)?;
+    let scheme = match value.get("schemes").and_then(|s| s.get(0)) {
+        Some(scheme) => scheme,
+        None => &value,
+    };
+    let get = |key: &str| scheme.get(key)?.as_str().and_then(parse_color_value);
+
+    let mut s = Slots::default();
+    s.set(Slots::BG, get("background"));
+    s.set(Slots::FG, get("foreground"));
+    s.set(Slots::SEL_BG, get("selectionBackground"));
+    s.set(Slots::CUR_BG, get("cursorColor"));
+    for (i, ansi) in ANSI_NAMES.iter().enumerate() {
+        s.set_ansi(i, get(ansi));
+        let mut bright = String::from("bright");
+        bright.push_str(&ansi[..1].to_uppercase());
+        bright.push_str(&ansi[1..]);
+        s.set_ansi(8 + i, get(&bright));
+    }
+    // Some exports use `purple` instead of `magenta`.
+    s.set_ansi(5, get("purple"));
+    s.set_ansi(13, get("brightPurple"));
+
+    let name = scheme.get("name").and_then(|n| n.as_str()).unwrap_or(name);
+    s.into_theme(name)
+}
+
+/// X resources: `*.background: #rrggbb`, `*color0: …`, `URxvt*color1: …`,
+/// with support for `#define NAME value` macros.
+pub fn parse_xresources(content: &str, name: &str) -> io::Result<Theme> {
+    let mut defines = Vec::<(String, String)>::new();
+    let mut s = Slots::default();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('!') {
+            continue;
+        }
+        if let Some(define) = line.strip_prefix("#define") {
+            let mut parts = define.split_whitespace();
+            if let (Some(k), Some(v)) = (parts.next(), parts.next()) {
+                defines.push((k.into(), v.into()));
+            }
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let value = defines
+            .iter()
+            .find(|(k, _)| k == value)
+            .map_or(value, |(_, v)| v.as_str());
+        let c = parse_color_value(value);
+
+        // Resource names may be qualified (`URxvt*color1`, `*.color1`).
+        let key = key.trim().rsplit(['*', '.']).next().unwrap_or_default();
+        match key {
+            "background" => s.set(Slots::BG, c),
+            "foreground" => s.set(Slots::FG, c),
+            "cursorColor" => s.set(Slots::CUR_BG, c),
+            "cursorColor2" => s.set(Slots::CUR_FG, c),
+            "highlightColor" => s.set(Slots::SEL_BG, c),
+            "highlightTextColor" => s.set(Slots::SEL_FG, c),
+            _ => {
+                if let Some(i) = key.strip_prefix("color").and_then(|i| i.parse().ok()) {
+                    s.set_ansi(i, c);
+                }
+            }
+        }
+    }
+
+    s.into_theme(name)
+}
+
+/// base16 scheme YAML (`scheme:`/`name:` plus `base00`..`base0F`), mapped
+/// to terminal colors the same way as base16-shell.
+pub fn parse_base16(content: &str, name: &str) -> io::Result<Theme> {
+    let mut base = [None::<Color>; 16];
+    let mut theme_name = None;
+
+    for line in content.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value
+            .split(" #")
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'');
+        match key.trim() {
+            "scheme" | "name" if theme_name.is_none() && !value.is_empty() => {
+                theme_name = Some(value.to_string());
+            }
+            key => {
+                if let Some(i) = key
+                    .strip_prefix("base0")
+                    .and_then(|i| usize::from_str_radix(i, 16).ok())
+                    .filter(|&i| i < base.len())
+                {
+                    base[i] = parse_color_value(value);
+                }
+            }
+        }
+    }
+
+    let b = |i: usize| base[i];
+    let mut s = Slots::default();
+    s.set(Slots::BG, b(0x0));
+    s.set(Slots::FG, b(0x5));
+    s.set(Slots::SEL_BG, b(0x2));
+    s.set(Slots::SEL_FG, b(0x5));
+    s.set(Slots::CUR_BG, b(0x5));
+    s.set(Slots::CUR_FG, b(0x0));
+    let ansi = [0x0, 0x8, 0xB, 0xA, 0xD, 0xE, 0xC, 0x5];
+    let bright = [0x3, 0x8, 0xB, 0xA, 0xD, 0xE, 0xC, 0x7];
+    for i in 0..8 {
+        s.set_ansi(i, b(ansi[i]));
+        s.set_ansi(8 + i, b(bright[i]));
+    }
+
+    s.into_theme(theme_name.as_deref().unwrap_or(name))
+}
+
+/// Replies to OSC color queries as sent back by a terminal, e.g.
+/// `ESC]11;rgb:1e1e/1e1e/2e2e ESC\`: OSC 4 palette, 10 fg, 11 bg,
+/// 12 cursor, 17 selection bg and 19 selection fg. Both BEL and ST
+/// terminators are accepted; anything else in between is skipped.
+pub fn parse_osc_replies(replies: &str, name: &str) -> io::Result<Theme> {
+    let mut s = Slots::default();
+
+    for reply in replies.split("\x1b]").skip(1) {
+        let reply = reply.split(['\x07', '\x1b']).next().unwrap_or_default();
+        let (code, value) = reply.split_once(';').unwrap_or_default();
+        match code {
+            "4" => {
+                if let Some((i, value)) = value.split_once(';')
+                    && let Ok(i) = i.parse()
+                {
+                    s.set_ansi(i, parse_color_value(value));
+                }
+            }
+            "10" => s.set(Slots::FG, parse_color_value(value)),
+            "11" => s.set(Slots::BG, parse_color_value(value)),
+            "12" => s.set(Slots::CUR_BG, parse_color_value(value)),
+            "17" => s.set(Slots::SEL_BG, parse_color_value(value)),
+            "19" => s.set(Slots::SEL_FG, parse_color_value(value)),
+            _ => {}
+        }
+    }
+
+    s.into_theme(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(t: &Theme) -> (&str, &str, &str) {
+        (
+            t.colors.bg.as_str(),
+            t.colors.fg.as_str(),
+            t.colors.base.red.as_str(),
+        )
+    }
+
+    #[test]
+    fn color_value_notations() {
+        let c = Color::from_hex(0x1a2b3c);
+        assert_eq!(parse_color_value("#1a2b3c"), Some(c));
+        assert_eq!(parse_color_value("'0x1A2B3C'"), Some(c));
+        assert_eq!(parse_color_value("\"1a2b3c\""), Some(c));
+        assert_eq!(parse_color_value("rgb:1a/2b/3c"), Some(c));
+        assert_eq!(parse_color_value("rgb:1a1a/2b2b/3c3c"), Some(c));
+        assert_eq!(parse_color_value("#fff"), Some(Color::from_hex(0xffffff)));
+        assert_eq!(parse_color_value("nope"), None);
+    }
+
+    #[test]
+    fn alacritty() {
+        let t = parse_alacritty(
+            r##"
+[colors.primary]
+background = "#101010"
+foreground = "0xe0e0e0"
+[colors.normal]
+red = "#cc0000"
+"##,
+            "a",
+        )
+        .unwrap();
+        assert_eq!(hex(&t), ("#101010", "#e0e0e0", "#cc0000"));
+        assert_eq!(t.colors.bright.red.as_str(), "#cc0000");
+        assert!(!t.is_light);
+    }
+
+    #[test]
+    fn kitty() {
+        let t = parse_kitty(
+            "## name: Kit\nbackground #fafafa\nforeground   #202020\ncolor1 #aa0000\n",
+            "k",
+        )
+        .unwrap();
+        assert_eq!(t.name, "Kit");
+        assert_eq!(hex(&t), ("#fafafa", "#202020", "#aa0000"));
+        assert!(t.is_light);
+    }
+
+    #[test]
+    fn iterm2() {
+        let component = |k: &str, r: f32, g: f32, b: f32| {
+            format!(
+                "<key>{k}</key><dict>\
+                 <key>Color Space</key><string>sRGB</string>\
+                 <key>Blue Component</key><real>{b}</real>\
+                 <key>Green Component</key><real>{g}</real>\
+                 <key>Red Component</key><real>{r}</real>\
+                 </dict>"
+            )
+        };
+        let content = format!(
+            "<?xml version=\"1.0\"?><plist version=\"1.0\"><dict>{}{}{}</dict></plist>",
+            component("Background Color", 0.0, 0.0, 0.0),
+            component("Foreground Color", 1.0, 1.0, 1.0),
+            component("Ansi 1 Color", 1.0, 0.0, 0.0),
+        );
+        let t = parse_iterm2(&content, "i").unwrap();
+        assert_eq!(hex(&t), ("#000000", "#ffffff", "#ff0000"));
+    }
+
+    #[test]
+    fn windows_terminal() {
+        let t = parse_windows_terminal(
+            r##"{"schemes": [{"name": "WT", "background": "#0C0C0C",
+                "foreground": "#CCCCCC", "red": "#C50F1F", "brightRed": "#E74856"}]}"##,
+            "w",
+        )
+        .unwrap();
+        assert_eq!(t.name, "WT");
+        assert_eq!(hex(&t), ("#0c0c0c", "#cccccc", "#c50f1f"));
+        assert_eq!(t.colors.bright.red.as_str(), "#e74856");
+    }
+
+    #[test]
+    fn xresources() {
+        let t = parse_xresources(
+            "! comment\n#define RED #aa1111\n*.background: #111111\nURxvt*foreground: #eeeeee\n*color1: RED\n",
+            "x",
+        )
+        .unwrap();
+        assert_eq!(hex(&t), ("#111111", "#eeeeee", "#aa1111"));
+    }
+
+    #[test]
+    fn base16() {
+        let t = parse_base16(
+            "scheme: \"B16\"\nauthor: \"me\"\nbase00: \"181818\"\nbase05: \"d8d8d8\"\nbase08: \"ab4642\" # red\n",
+            "b",
+        )
+        .unwrap();
+        assert_eq!(t.name, "B16");
+        assert_eq!(hex(&t), ("#181818", "#d8d8d8", "#ab4642"));
+    }
+
+    #[test]
+    fn osc_replies() {
+        let t = parse_osc_replies(
+            "\x1b]11;rgb:1e1e/1e1e/2e2e\x1b\\\x1b]10;rgb:cdcd/d6d6/f4f4\x07\
+             \x1b]4;1;rgb:f3/8b/a8\x1b\\\x1b]4;300;rgb:00/00/00\x07\x1b[?62;22c",
+            "captured",
+        )
+        .unwrap();
+        assert_eq!(hex(&t), ("#1e1e2e", "#cdd6f4", "#f38ba8"));
+        assert_eq!(t.colors.cursor.bg, t.colors.fg);
+        assert!(parse_osc_replies("\x1b[?62;22c", "c").is_err());
+    }
+
+    #[test]
+    fn base16_ignores_out_of_range_keys() {
+        let t = parse_base16(
+            "base00: \"181818\"\nbase05: \"d8d8d8\"\nbase0AB: \"ffffff\"\nbase0: \"ffffff\"\n",
+            "b",
+        )
+        .unwrap();
+        assert_eq!(t.colors.bg.as_str(), "#181818");
+    }
+
+    #[test]
+    fn missing_background_is_an_error() {
+        assert!(parse_kitty("foreground #ffffff\n", "k").is_err());
+    }
+}
@@ -1,7 +1,13 @@
 mod adjustments;
 mod collection;
 mod color;
+mod contrast;
+mod cvd;
 mod error;
+mod extract;
+mod generate;
+mod import;
+mod palette;
 mod theme;
 
 pub mod fuzzy;
@@ -9,5 +15,10 @@ pub mod fuzzy;
 pub use adjustments::*;
 pub use collection::*;
 pub use color::*;
+pub use contrast::*;
+pub use cvd::*;
 pub use error::*;
+pub use generate::*;
+pub use import::*;
+pub use palette::*;
 pub use theme::*;
@@ -0,0 +1,101 @@
+//! Extended xterm palette (indices 16–255) derived from a [`ColorScheme`].
+//!
+//! The stock 256-color palette is fixed and clashes with any theme. Here the
+//! cube corners are taken from the theme instead and everything in between
+//! is interpolated in CIE Lab:
+//!
+//! ```text
+//! 16–231    6×6×6 cube, corners: black→bg, white→fg, red … cyan → base colors
+//! 232–255   grayscale ramp from bg to fg (ends excluded)
+//! ```
+
+use crate::{Color, ColorScheme};
+
+/// First index of the extended palette.
+pub const EXTENDED_PALETTE_START: usize = 16;
+
+/// Number of colors in the extended palette: 216 cube + 24 grayscale.
+pub const EXTENDED_PALETTE_NC: usize = 6 * 6 * 6 + 24;
+
+fn lerp_lab(a: (f32, f32, f32), b: (f32, f32, f32), t: f32) -> (f32, f32, f32) {
+    (
+        a.0 + (b.0 - a.0) * t,
+        a.1 + (b.1 - a.1) * t,
+        a.2 + (b.2 - a.2) * t,
+    )
+}
+
+impl ColorScheme {
+    /// Colors for xterm indices 16–255; element `i` is index
+    /// `EXTENDED_PALETTE_START + i`.
+    pub fn extended_palette(&self) -> [Color; EXTENDED_PALETTE_NC] {
+        let lab = |c: &crate::CssColor| c.color().lab();
+        let bg = lab(&self.bg);
+        let fg = lab(&self.fg);
+        let a = &self.base;
+        let (red, green, yellow, blue, magenta, cyan) = (
+            lab(&a.red),
+            lab(&a.green),
+            lab(&a.yellow),
+            lab(&a.blue),
+            lab(&a.magenta),
+            lab(&a.cyan),
+        );
+
+        let mut out = [Color::default(); EXTENDED_PALETTE_NC];
+        let mut i = 0;
+        for r in 0..6 {
+            let r = r as f32 / 5.0;
+            // Cube edges along the red axis, for each green/blue corner.
+            let c0 = lerp_lab(bg, red, r);
+            let c1 = lerp_lab(green, yellow, r);
+            let c2 = lerp_lab(blue, magenta, r);
+            let c3 = lerp_lab(cyan, fg, r);
+            for g in 0..6 {
+                let g = g as f32 / 5.0;
+                let c4 = lerp_lab(c0, c1, g);
+                let c5 = lerp_lab(c2, c3, g);
+                for b in 0..6 {
+                    let (l, a, b) = lerp_lab(c4, c5, b as f32 / 5.0);
+                    out[i] = Color::from_lab(l, a, b);
+                    i += 1;
+                }
+            }
+        }
+        for step in 0..24 {
+            let (l, a, b) = lerp_lab(bg, fg, (step + 1) as f32 / 25.0);
+            out[i] = Color::from_lab(l, a, b);
+            i += 1;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::COLOR_SCHEME_NC;
+
+    #[test]
+    fn cube_corners_follow_theme() {
+        let mut colors = [Color::from_hex(0x808080); COLOR_SCHEME_NC];
+        colors[0] = Color::from_hex(0x1e1e2e);
+        colors[1] = Color::from_hex(0xcdd6f4);
+        colors[7] = Color::from_hex(0xf38ba8);
+        colors[10] = Color::from_hex(0x89b4fa);
+        let cs = ColorScheme::from_color_slice(&colors);
+        let p = cs.extended_palette();
+        let at = |index: usize| p[index - EXTENDED_PALETTE_START].css();
+
+        assert_eq!(at(16), cs.bg);
+        assert_eq!(at(231), cs.fg);
+        assert_eq!(at(196), cs.base.red);
+        assert_eq!(at(21), cs.base.blue);
+
+        // Grayscale ramp gets lighter from bg toward fg.
+        let ramp = p[216..].iter().map(|c| c.lab().0).collect::<Vec<_>>();
+        assert!(ramp.windows(2).all(|w| w[0] < w[1]));
+        assert!(ramp[0] > cs.bg.color().lab().0);
+        assert!(ramp[23] < cs.fg.color().lab().0);
+    }
+}
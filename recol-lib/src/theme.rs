@@ -81,6 +81,15 @@ impl Theme {
         buf
     }
 
+    /// Theme between `self` (`t = 0.0`) and `other` (`t = 1.0`), see
+    /// [`ColorScheme::interpolate`]. Light or dark follows the blended
+    /// background.
+    pub fn interpolate(&self, other: &Theme, t: f32, name: impl Into<String>) -> Self {
+        let colors = self.colors.interpolate(&other.colors, t);
+        let is_light = colors.bg.color().lab().0 > 50.0;
+        Self::new(name, is_light, colors)
+    }
+
     pub fn print_palette(&self) {
         crate::print_palette(&self.colors.as_colors_array()[0..14]);
     }
@@ -324,6 +333,17 @@ impl ColorScheme {
     pub fn apply_adjustments(&mut self, adjusts: &[ThemeAdjustment]) {
         adjusts.iter().for_each(|a| self.apply_adjustment(a));
     }
+
+    /// Blends every stored color toward `other` in OKLab.
+    ///
+    /// `t = 0.0` returns `self`; `t = 1.0` returns `other`. `t` is clamped to
+    /// `[0.0, 1.0]`.
+    pub fn interpolate(&self, other: &ColorScheme, t: f32) -> ColorScheme {
+        let t = t.clamp(0.0, 1.0);
+        let a = self.as_colors_array();
+        let b = other.as_colors_array();
+        ColorScheme::from_color_slice(&std::array::from_fn(|i| a[i].blend_oklab(&b[i], t)))
+    }
 }
 
 impl TryFrom<&[u8]> for ColorScheme {
@@ -373,8 +393,10 @@ pub struct AnsiColors {
     pub cyan: CssColor,
     pub white: CssColor,
     /// Derived: `blend(red, yellow, 50 %)`. Not stored in binary.
+    #[serde(default)]
     pub orange: CssColor,
     /// Derived: `blend(red, white, 50 %)`. Not stored in binary.
+    #[serde(default)]
     pub pink: CssColor,
 }
 
@@ -429,6 +451,23 @@ pub struct AdvancedColorScheme {
     pub comment: CssColor,
 }
 
+impl AdvancedColorScheme {
+    /// Collapse back into a [`ColorScheme`], keeping the main `bg`/`fg`
+    /// (index 1 of each gradient) and dropping the derived entries.
+    pub fn into_color_scheme(self) -> ColorScheme {
+        let [_, bg, ..] = self.bg;
+        let [_, fg, ..] = self.fg;
+        ColorScheme {
+            bg,
+            fg,
+            selection: self.selection,
+            cursor: self.cursor,
+            base: self.base,
+            bright: self.bright,
+        }
+    }
+}
+
 /// Tuning knobs for [`ColorScheme::into_advanced`].
 ///
 /// All `*_brighten` values are additive HSL lightness deltas (positive =
@@ -479,3 +518,27 @@ impl Default for AdvancedColorSchemeParam {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolate_ends_and_midpoint() {
+        let dark = ColorScheme::from_color_slice(&[Color::from_hex(0x1e1e2e); COLOR_SCHEME_NC]);
+        let light = ColorScheme::from_color_slice(&[Color::from_hex(0xeff1f5); COLOR_SCHEME_NC]);
+
+        assert_eq!(dark.interpolate(&light, 0.0).bg, dark.bg);
+        assert_eq!(dark.interpolate(&light, 1.0).bg, light.bg);
+        assert_eq!(dark.interpolate(&light, 7.0).fg, light.fg);
+
+        let mid = dark.interpolate(&light, 0.5).bg.color().oklab().0;
+        let (l1, l2) = (dark.bg.color().oklab().0, light.bg.color().oklab().0);
+        assert!((mid - (l1 + l2) / 2.0).abs() < 0.01);
+
+        let a = Theme::new("a", false, dark);
+        let b = Theme::new("b", true, light);
+        assert!(!a.interpolate(&b, 0.2, "ab").is_light);
+        assert!(a.interpolate(&b, 0.8, "ab").is_light);
+    }
+}
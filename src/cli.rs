@@ -1,6 +1,9 @@
 use recol_lib::{self as lib, parse_theme_adjustments, ThemeAdjustment};
 
-use crate::targets::{self, Target};
+use crate::{
+    export::{self, ExportFormat},
+    targets::{self, Target},
+};
 
 #[derive(Clone, Debug, Default)]
 pub struct Args {
@@ -19,14 +22,41 @@ pub struct Args {
     /// Filter themes by name substring
     pub contains: Option<String>,
 
+    /// Filter themes by colors, e.g. "bg~#1e1e2e,contrast>7"
+    pub where_filters: Vec<lib::ThemeFilter<'static>>,
+
     pub adjust: Vec<ThemeAdjustment>,
 
+    /// Save the theme with adjustments applied as a new user theme
+    pub save_as: Option<String>,
+
+    /// Generate a theme from an accent color
+    pub generate: Option<String>,
+
+    /// Extract a theme from a PNG/JPEG image
+    pub from_image: Option<String>,
+
+    /// Read the running terminal's colors as a theme, optionally named
+    pub capture: Option<String>,
+
+    /// Blend two themes by name (fuzzy matching)
+    pub mix: Vec<String>,
+
+    /// Position between the mixed themes, 0.0..=1.0
+    pub mix_ratio: Option<f32>,
+
+    /// Import a theme file from another terminal or editor as a user theme
+    pub import: Option<String>,
+
     /// Neovim config path
     pub nvim_config: Option<String>,
 
     /// List available themes
     pub theme_list: bool,
 
+    /// List themes whose colors are closest to this theme
+    pub like: Option<String>,
+
     /// Set font family by name (fuzzy matching)
     pub font: Option<String>,
 
@@ -42,9 +72,34 @@ pub struct Args {
     /// Output theme as JSON
     pub json: bool,
 
+    /// Print the WCAG contrast report of the theme
+    pub audit: bool,
+
+    /// Show the theme in the running terminal via OSC sequences, without
+    /// editing configs
+    pub preview: bool,
+
+    /// Export the theme as a standalone file for another program
+    pub export: Option<ExportFormat>,
+
+    /// Write the export to a file instead of stdout
+    pub output: Option<String>,
+
     /// Apply for specific target
     pub targets: Vec<Target>,
 
+    /// Print a diff of every config change instead of writing it
+    pub dry_run: bool,
+
+    /// Restore the files changed by the last run
+    pub undo: bool,
+
+    /// List config backups
+    pub backups: bool,
+
+    /// Restore the config backup with this number (1 is the newest)
+    pub restore_backup: Option<usize>,
+
     /// Run interactive mode
     pub interactive: bool,
 
@@ -94,9 +149,10 @@ fn help() -> String {
 {magenta}https://github.com/nlkli/recol{reset}
 
 {green}Supported targets:{reset}
-alacritty, ghostty, wezterm, neovim, vim.
+alacritty, ghostty, wezterm, kitty, foot, tmux, zellij, neovim, vim, helix, template.
 
 {green}Usage:{reset} {blue}recol [OPTIONS] [THEME_NAME]{reset}
+       {blue}recol import <FILE>{reset}
 
 {green}Options:{reset}
   {blue}-t{reset}, {blue}--theme <NAME>{reset}
@@ -106,10 +162,29 @@ alacritty, ghostty, wezterm, neovim, vim.
   {blue}-d{reset}, {blue}--dark{reset}; {blue}-l{reset}, {blue}--light{reset}
   {blue}-c{reset}, {blue}--contains <STR>{reset}
       Filter themes by dark, light or name substring
-      (used with --rand, --theme or --theme-list)
+      (used with --rand, --theme, --theme-list or --like)
+  {blue}-w{reset}, {blue}--where <EXPR>{reset}
+      Filter themes by colors, same use as --contains. Comma-separated:
+      bg~COLOR[:D] (bg within distance D, default 10), l>N / l<N
+      (bg lightness 0..100), contrast>N / c<N (fg/bg ratio), warm, cool
   {blue}-a{reset}, {blue}--adjust <SPEC|PATH>{reset} [env: RECOL_ADJUST]
       Apply color adjustments (see --adjust help)
       Format: "group.adjustment=value,..."
+  {blue}-g{reset}, {blue}--generate <COLOR>{reset}
+      Generate a theme from an accent color (dark, or light with --light)
+  {blue}--from-image <PATH>{reset}
+      Extract a theme from a PNG/JPEG image (force with --dark/--light)
+  {blue}--capture [NAME]{reset}
+      Read the running terminal's colors (OSC queries) as a theme, to
+      show, save with --save-as, export or apply to the other targets
+  {blue}--mix <NAME> <NAME> [T]{reset}
+      Blend two themes in OKLab, T from 0.0 (first) to 1.0 (second),
+      default 0.5
+  {blue}--save-as <NAME>{reset}
+      Save the theme with adjustments baked in as a user theme
+  {blue}--import <FILE>{reset}
+      Import a theme as a user theme (Alacritty, kitty, iTerm2,
+      Windows Terminal, Xresources, base16, Ghostty or recol JSON)
   {blue}-i{reset}, {blue}--interactive{reset}
       Browse and apply themes interactively
   {blue}-f{reset}, {blue}--font <NAME>{reset}
@@ -118,11 +193,30 @@ alacritty, ghostty, wezterm, neovim, vim.
       Pick a random Nerd Font
   {blue}-T{reset}, {blue}--target <Name>{reset}
       Apply for specific target (see --target list)
+  {blue}--dry-run{reset}
+      Print a unified diff of every config change instead of writing it
+  {blue}--undo{reset}
+      Restore the config files changed by the last run
+  {blue}--backups [N]{reset}
+      List config backups, or restore backup N (1 is the newest)
   {blue}-L{reset}, {blue}--theme-list{reset}  List available themes
+  {blue}--like <NAME>{reset}
+      List the themes that look most like NAME (by palette, not name)
   {blue}--font-list{reset}       List available Nerd Fonts
   {blue}-s{reset}, {blue}--show{reset}
       Show the theme color palette without applying it
   {blue}-j{reset}, {blue}--json{reset}  Output theme/list as JSON
+  {blue}--preview{reset}, {blue}--osc{reset}
+      Recolor the running terminal via OSC sequences until a key is
+      pressed, without editing configs (live preview with --interactive)
+  {blue}--audit{reset}
+      Check WCAG contrast of text, selection, cursor and ANSI colors
+      against the background (current theme if none given)
+  {blue}-e{reset}, {blue}--export <FORMAT>{reset}
+      Print the theme in another format without applying it
+      (see --export list)
+  {blue}-o{reset}, {blue}--output <PATH>{reset}
+      Write the export to a file instead of stdout
   {blue}-h{reset}, {blue}--help{reset}; {blue}-V{reset}, {blue}--version{reset}; {blue}--logo{reset}"#,
         reset = RESET,
         green = GREEN,
@@ -141,8 +235,12 @@ fn adjust_help() -> String {
   {blue}--adjust "saturation=20"{reset}       Boost all colors evenly
   {blue}--adjust "temperature=20,tint=-10"{reset}   Warmer + slight green tint
   {blue}--adjust "pal.hue=180"{reset}         Rotate ANSI palette hues
+  {blue}--adjust "pal.hue@oklch=30"{reset}   Rotate hues, keep perceived lightness
   {blue}--adjust "sel.invert,cur.hue=90"{reset}  Invert selection, green cursor
   {blue}--adjust "pal.normalize=50,pal.vibrance=-20"{reset}  Unify palette & desaturate
+  {blue}--adjust "pal.min-contrast=4.5"{reset}  Make palette readable (WCAG AA)
+  {blue}--adjust "cvd.deutan=100"{reset}     Preview as seen with red-green color blindness
+  {blue}--adjust "pal.daltonize=100"{reset}  Keep red and green apart for deutans
   {blue}--adjust "preset.txt"{reset}          Load adjustments from file
   {blue}--adjust "_"{reset}                   Reset all adjustments
 
@@ -178,7 +276,15 @@ fn adjust_help() -> String {
   {blue}ti/tint{reset}=N             Green↔Magenta axis (negative = greener)
   {blue}n/norm/normalize{reset}=N    Pull lightness toward group average
   {blue}nb/norm-both{reset}=N        Pull lightness + chroma toward average
-  {blue}nc/norm-chroma{reset}=N      Pull chroma toward group average"#,
+  {blue}nc/norm-chroma{reset}=N      Pull chroma toward group average
+  {blue}mc/min-contrast{reset}=R     Raise contrast against bg to ratio R (1..21, e.g. 4.5)
+  {blue}cvd.protan/deutan/tritan{reset}=N  Simulate color blindness, N = severity (0..100)
+  {blue}dz/daltonize{reset}=N        Shift colors apart for deutans (0..100)
+  {blue}dz.protan/dz.tritan{reset}=N  Same for protans / tritans
+
+{green}Color space (suffix):{reset}
+  {blue}@oklch{reset}          Run b/s/h/n/nb/nc in perceptual OKLCH instead of HSL/HSV
+                  e.g. "pal.saturation@oklch=20""#,
         reset = RESET,
         green = GREEN,
         blue = BLUE,
@@ -190,12 +296,16 @@ impl Args {
         let mut args = Self::default();
         let mut last: Option<char> = None;
 
-        if let Some(arg) = std::env::var("RECOL_ADJUST").ok() {
+        if let Ok(arg) = std::env::var("RECOL_ADJUST") {
             args.adjust_arg(arg);
         }
 
-        let mut iter = std::env::args().skip(1);
-        while let Some(arg) = iter.next() {
+        for (i, arg) in std::env::args().skip(1).enumerate() {
+            if i == 0 && arg == "import" {
+                last = Some('I');
+                continue;
+            }
+
             if let Some(flag) = arg.strip_prefix("--") {
                 match flag {
                     "theme" => last = Some('t'),
@@ -204,6 +314,19 @@ impl Args {
                     "target" => last = Some('c'),
                     "nvim_config" => last = Some('0'),
                     "adjust" => last = Some('a'),
+                    "save-as" => last = Some('S'),
+                    "import" => last = Some('I'),
+                    "generate" => last = Some('g'),
+                    "from-image" => last = Some('m'),
+                    "mix" => last = Some('x'),
+                    "like" => last = Some('K'),
+                    "capture" => {
+                        args.capture.get_or_insert_default();
+                        last = Some('C');
+                    }
+                    "where" => last = Some('w'),
+                    "export" => last = Some('e'),
+                    "output" => last = Some('o'),
                     "theme-list" => args.theme_list = true,
                     "font-list" => args.font_list = true,
                     "font-rand" => args.font_rand = true,
@@ -212,6 +335,14 @@ impl Args {
                     "light" => args.light = true,
                     "show" => args.show = true,
                     "json" => args.json = true,
+                    "audit" => args.audit = true,
+                    "preview" | "osc" => args.preview = true,
+                    "dry-run" => args.dry_run = true,
+                    "undo" => args.undo = true,
+                    "backups" => {
+                        args.backups = true;
+                        last = Some('B');
+                    }
                     "interactive" => args.interactive = true,
                     "quit-on-select" => args.quit_on_select = true,
                     "init-input" => args.init_input = true,
@@ -233,7 +364,7 @@ impl Args {
             } else if let Some(flags) = arg.strip_prefix('-') {
                 for c in flags.chars() {
                     match c {
-                        't' | 'f' | 'c' | 'T' | 'a' => last = Some(c),
+                        't' | 'f' | 'c' | 'w' | 'T' | 'a' | 'e' | 'o' | 'g' => last = Some(c),
                         'r' => args.rand = true,
                         'd' => args.dark = true,
                         'l' => args.light = true,
@@ -264,6 +395,10 @@ impl Args {
                     Some('c') => {
                         args.contains.replace(arg);
                     }
+                    Some('w') => match lib::parse_theme_filters(&arg) {
+                        Ok(filters) => args.where_filters.extend(filters),
+                        Err(e) => panic!("{e}"),
+                    },
                     Some('0') => {
                         args.nvim_config.replace(arg);
                     }
@@ -283,6 +418,68 @@ impl Args {
                     Some('a') => {
                         args.adjust_arg(arg);
                     }
+                    Some('S') => {
+                        args.save_as.replace(arg);
+                    }
+                    Some('I') => {
+                        args.import.replace(arg);
+                    }
+                    Some('g') => {
+                        args.generate.replace(arg);
+                    }
+                    Some('m') => {
+                        args.from_image.replace(arg);
+                    }
+                    Some('C') => {
+                        args.capture.replace(arg);
+                    }
+                    Some('B') => match arg.parse::<usize>() {
+                        Ok(n) => {
+                            args.restore_backup.replace(n);
+                        }
+                        Err(_) => {
+                            args.theme.replace(arg);
+                        }
+                    },
+                    Some('K') => {
+                        args.like.replace(arg);
+                    }
+                    Some('x') => {
+                        args.mix.push(arg);
+                        last = Some(if args.mix.len() < 2 { 'x' } else { 'X' });
+                    }
+                    Some('X') => match arg.parse::<f32>() {
+                        Ok(t) => {
+                            args.mix_ratio.replace(t);
+                        }
+                        Err(_) => {
+                            args.theme.replace(arg);
+                        }
+                    },
+                    Some('e') => {
+                        if arg == "list" {
+                            for f in export::ALL_FORMATS {
+                                println!("{}", f);
+                            }
+                            std::process::exit(0);
+                        }
+                        match arg.parse::<ExportFormat>() {
+                            Ok(f) => {
+                                args.export.replace(f);
+                            }
+                            Err(_) => {
+                                let formats = export::ALL_FORMATS.map(|f| f.to_string());
+                                eprintln!(
+                                    "unknown export format: {arg} (expected one of: {})",
+                                    formats.join(", ")
+                                );
+                                std::process::exit(1);
+                            }
+                        }
+                    }
+                    Some('o') => {
+                        args.output.replace(arg);
+                    }
                     _ => {
                         args.theme.replace(arg);
                     }
@@ -304,6 +501,7 @@ impl Args {
         if let Some(s) = &self.contains {
             filters.push(lib::ThemeFilter::Contains(s));
         }
+        filters.extend_from_slice(&self.where_filters);
         filters
     }
 
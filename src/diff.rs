@@ -0,0 +1,107 @@
+//! Line-based unified diff for `--dry-run`.
+
+/// Unchanged lines shown around each change.
+const CONTEXT: usize = 3;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Shortest edit script from `a` to `b`, by longest common subsequence.
+/// Config files are small, so the quadratic table is fine once the common
+/// prefix and suffix are cut off.
+fn edits<'a>(a: &[&'a str], b: &[&'a str]) -> Vec<(Op, &'a str)> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+    let (n, m) = (a_mid.len(), b_mid.len());
+
+    // lcs[i][j]: length of the LCS of a_mid[i..] and b_mid[j..].
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if a_mid[i] == b_mid[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = a[..prefix]
+        .iter()
+        .map(|l| (Op::Equal, *l))
+        .collect::<Vec<_>>();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && a_mid[i] == b_mid[j] {
+            out.push((Op::Equal, a_mid[i]));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out.push((Op::Delete, a_mid[i]));
+            i += 1;
+        } else {
+            out.push((Op::Insert, b_mid[j]));
+            j += 1;
+        }
+    }
+    out.extend(a[a.len() - suffix..].iter().map(|l| (Op::Equal, *l)));
+    out
+}
+
+/// Unified diff from `old` to `new`, or `None` when they are the same.
+/// `None` on either side stands for a file that does not exist.
+pub fn unified(path: &str, old: Option<&str>, new: Option<&str>) -> Option<String> {
+    let a = old.unwrap_or_default().lines().collect::<Vec<_>>();
+    let b = new.unwrap_or_default().lines().collect::<Vec<_>>();
+    let ops = edits(&a, &b);
+    let changes = (0..ops.len())
+        .filter(|&k| ops[k].0 != Op::Equal)
+        .collect::<Vec<_>>();
+    if changes.is_empty() && old.is_some() == new.is_some() {
+        return None;
+    }
+
+    let name = |side: Option<&str>| if side.is_some() { path } else { "/dev/null" };
+    let mut out = format!("--- {}\n+++ {}\n", name(old), name(new));
+
+    let mut k = 0;
+    while k < changes.len() {
+        // Merge changes whose context would touch or overlap.
+        let first = changes[k];
+        while k + 1 < changes.len() && changes[k + 1] - changes[k] <= 2 * CONTEXT + 1 {
+            k += 1;
+        }
+        let start = first.saturating_sub(CONTEXT);
+        let end = (changes[k] + CONTEXT + 1).min(ops.len());
+        k += 1;
+
+        let count = |ops: &[(Op, &str)], skip: Op| ops.iter().filter(|(op, _)| *op != skip).count();
+        let (old_len, new_len) = (
+            count(&ops[start..end], Op::Insert),
+            count(&ops[start..end], Op::Delete),
+        );
+        // Unified diff numbers an empty range by the line before it.
+        let old_start = count(&ops[..start], Op::Insert) + usize::from(old_len > 0);
+        let new_start = count(&ops[..start], Op::Delete) + usize::from(new_len > 0);
+        out += &format!("@@ -{old_start},{old_len} +{new_start},{new_len} @@\n");
+        for (op, line) in &ops[start..end] {
+            let sign = match op {
+                Op::Equal => ' ',
+                Op::Delete => '-',
+                Op::Insert => '+',
+            };
+            out += &format!("{sign}{line}\n");
+        }
+    }
+    Some(out)
+}
@@ -0,0 +1,316 @@
+//! Standalone theme files for other terminals and editors, printed or written
+//! without touching any config.
+
+use crate::targets::{self, alacritty, vim};
+use recol_lib::{self as lib, CssColor};
+use serde::Serialize;
+use std::fmt::{self, Write};
+
+pub const ALL_FORMATS: [ExportFormat; 8] = [
+    ExportFormat::Alacritty,
+    ExportFormat::Kitty,
+    ExportFormat::Ghostty,
+    ExportFormat::WindowsTerminal,
+    ExportFormat::Iterm2,
+    ExportFormat::Xresources,
+    ExportFormat::Base16,
+    ExportFormat::Vim,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Alacritty,
+    Kitty,
+    Ghostty,
+    WindowsTerminal,
+    Iterm2,
+    Xresources,
+    Base16,
+    Vim,
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Alacritty => "alacritty",
+            Self::Kitty => "kitty",
+            Self::Ghostty => "ghostty",
+            Self::WindowsTerminal => "windows-terminal",
+            Self::Iterm2 => "iterm2",
+            Self::Xresources => "xresources",
+            Self::Base16 => "base16",
+            Self::Vim => "vim",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "al" | "alacritty" => Ok(Self::Alacritty),
+            "k" | "kt" | "kitty" => Ok(Self::Kitty),
+            "gh" | "ghostty" => Ok(Self::Ghostty),
+            "wt" | "windows-terminal" => Ok(Self::WindowsTerminal),
+            "iterm" | "iterm2" | "itermcolors" => Ok(Self::Iterm2),
+            "xresources" | "xrdb" => Ok(Self::Xresources),
+            "base16" => Ok(Self::Base16),
+            "v" | "vim" | "nvim" | "neovim" => Ok(Self::Vim),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The 16 ANSI colors in palette order.
+fn ansi16(c: &lib::ColorScheme) -> [&CssColor; 16] {
+    [
+        &c.base.black,
+        &c.base.red,
+        &c.base.green,
+        &c.base.yellow,
+        &c.base.blue,
+        &c.base.magenta,
+        &c.base.cyan,
+        &c.base.white,
+        &c.bright.black,
+        &c.bright.red,
+        &c.bright.green,
+        &c.bright.yellow,
+        &c.bright.blue,
+        &c.bright.magenta,
+        &c.bright.cyan,
+        &c.bright.white,
+    ]
+}
+
+fn alacritty(theme: &lib::Theme) -> String {
+    #[derive(Serialize)]
+    struct Theme {
+        colors: alacritty::Colors,
+    }
+
+    let content = toml::to_string(&Theme {
+        colors: alacritty::Colors::from_color_scheme(&theme.colors),
+    })
+    .unwrap_or_default();
+    format!("# {}\n\n{content}", theme.name)
+}
+
+fn kitty(theme: &lib::Theme) -> String {
+    let c = &theme.colors;
+    let mut out = format!("## name: {}\n\n", theme.name);
+    let _ = writeln!(out, "background {}", c.bg);
+    let _ = writeln!(out, "foreground {}", c.fg);
+    let _ = writeln!(out, "selection_background {}", c.selection.bg);
+    let _ = writeln!(out, "selection_foreground {}", c.selection.fg);
+    let _ = writeln!(out, "cursor {}", c.cursor.bg);
+    let _ = writeln!(out, "cursor_text_color {}", c.cursor.fg);
+    for (i, color) in ansi16(c).into_iter().enumerate() {
+        let _ = writeln!(out, "color{i} {color}");
+    }
+    out
+}
+
+fn ghostty(theme: &lib::Theme) -> String {
+    let c = &theme.colors;
+    let mut out = format!("# {}\n", theme.name);
+    for (i, color) in ansi16(c).into_iter().enumerate() {
+        let _ = writeln!(out, "palette = {i}={color}");
+    }
+    for (i, color) in targets::indexed_colors(c) {
+        let _ = writeln!(out, "palette = {i}={color}");
+    }
+    let _ = writeln!(out, "background = {}", c.bg);
+    let _ = writeln!(out, "foreground = {}", c.fg);
+    let _ = writeln!(out, "cursor-color = {}", c.cursor.bg);
+    let _ = writeln!(out, "cursor-text = {}", c.cursor.fg);
+    let _ = writeln!(out, "selection-background = {}", c.selection.bg);
+    let _ = writeln!(out, "selection-foreground = {}", c.selection.fg);
+    out
+}
+
+fn windows_terminal(theme: &lib::Theme) -> String {
+    const NAMES: [&str; 16] = [
+        "black",
+        "red",
+        "green",
+        "yellow",
+        "blue",
+        "purple",
+        "cyan",
+        "white",
+        "brightBlack",
+        "brightRed",
+        "brightGreen",
+        "brightYellow",
+        "brightBlue",
+        "brightPurple",
+        "brightCyan",
+        "brightWhite",
+    ];
+
+    let c = &theme.colors;
+    let mut scheme = serde_json::Map::new();
+    scheme.insert("name".into(), theme.name.as_str().into());
+    scheme.insert("background".into(), c.bg.as_str().into());
+    scheme.insert("foreground".into(), c.fg.as_str().into());
+    scheme.insert("cursorColor".into(), c.cursor.bg.as_str().into());
+    scheme.insert("selectionBackground".into(), c.selection.bg.as_str().into());
+    for (name, color) in NAMES.into_iter().zip(ansi16(c)) {
+        scheme.insert(name.into(), color.as_str().into());
+    }
+    serde_json::to_string_pretty(&scheme).unwrap_or_default() + "\n"
+}
+
+fn iterm2(theme: &lib::Theme) -> String {
+    let c = &theme.colors;
+    let mut entries = ansi16(c)
+        .into_iter()
+        .enumerate()
+        .map(|(i, color)| (format!("Ansi {i} Color"), color))
+        .collect::<Vec<_>>();
+    entries.extend([
+        ("Background Color".into(), &c.bg),
+        ("Cursor Color".into(), &c.cursor.bg),
+        ("Cursor Text Color".into(), &c.cursor.fg),
+        ("Foreground Color".into(), &c.fg),
+        ("Selected Text Color".into(), &c.selection.fg),
+        ("Selection Color".into(), &c.selection.bg),
+    ]);
+
+    let mut out = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+"#,
+    );
+    for (key, color) in entries {
+        let color = color.color();
+        let _ = write!(
+            out,
+            r#"	<key>{key}</key>
+	<dict>
+		<key>Alpha Component</key>
+		<real>1</real>
+		<key>Blue Component</key>
+		<real>{b}</real>
+		<key>Color Space</key>
+		<string>sRGB</string>
+		<key>Green Component</key>
+		<real>{g}</real>
+		<key>Red Component</key>
+		<real>{r}</real>
+	</dict>
+"#,
+            r = color.r,
+            g = color.g,
+            b = color.b,
+        );
+    }
+    out.push_str("</dict>\n</plist>\n");
+    out
+}
+
+fn xresources(theme: &lib::Theme) -> String {
+    let c = &theme.colors;
+    let mut out = format!("! {}\n", theme.name);
+    let _ = writeln!(out, "*.background: {}", c.bg);
+    let _ = writeln!(out, "*.foreground: {}", c.fg);
+    let _ = writeln!(out, "*.cursorColor: {}", c.cursor.bg);
+    for (i, color) in ansi16(c).into_iter().enumerate() {
+        let _ = writeln!(out, "*.color{i}: {color}");
+    }
+    out
+}
+
+/// Follows the base16-shell mapping that [`lib::parse_base16`] reads. Base16
+/// has no slots for black, white or the bright hues, so a re-import keeps bg,
+/// fg, selection bg, bright black and white and the normal hues, and fills
+/// the rest from those.
+fn base16(theme: &lib::Theme) -> String {
+    let c = theme.colors.clone().into_advanced(None);
+    let base = [
+        &c.bg[1],
+        &c.bg[2],
+        &c.selection.bg,
+        &c.bright.black,
+        &c.fg[3],
+        &c.fg[1],
+        &c.fg[0],
+        &c.bright.white,
+        &c.base.red,
+        &c.base.orange,
+        &c.base.yellow,
+        &c.base.green,
+        &c.base.cyan,
+        &c.base.blue,
+        &c.base.magenta,
+        &c.base.pink,
+    ];
+
+    let mut out = format!("scheme: \"{}\"\nauthor: \"recol\"\n", theme.name);
+    for (i, color) in base.into_iter().enumerate() {
+        let _ = writeln!(
+            out,
+            "base0{i:X}: \"{}\"",
+            color.as_str().trim_start_matches('#')
+        );
+    }
+    out
+}
+
+fn vim(theme: &lib::Theme) -> String {
+    format!(
+        "\" {}\n\" Generated by recol.\n{}\n",
+        theme.name,
+        vim::colorscheme(theme)
+    )
+}
+
+/// Renders `theme` as a standalone file in `format`.
+pub fn export_theme(theme: &lib::Theme, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Alacritty => alacritty(theme),
+        ExportFormat::Kitty => kitty(theme),
+        ExportFormat::Ghostty => ghostty(theme),
+        ExportFormat::WindowsTerminal => windows_terminal(theme),
+        ExportFormat::Iterm2 => iterm2(theme),
+        ExportFormat::Xresources => xresources(theme),
+        ExportFormat::Base16 => base16(theme),
+        ExportFormat::Vim => vim(theme),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base16_imports_back() {
+        for theme in lib::Collection::new().take(20).map(|t| t.into_theme()) {
+            let exported = export_theme(&theme, ExportFormat::Base16);
+            let imported = lib::parse_base16(&exported, "").unwrap();
+            let (a, b) = (&theme.colors, &imported.colors);
+
+            assert_eq!(imported.name, theme.name);
+            for (x, y) in [
+                (&a.bg, &b.bg),
+                (&a.fg, &b.fg),
+                (&a.selection.bg, &b.selection.bg),
+                (&a.bright.black, &b.bright.black),
+                (&a.bright.white, &b.bright.white),
+                (&a.base.red, &b.base.red),
+                (&a.base.green, &b.base.green),
+                (&a.base.yellow, &b.base.yellow),
+                (&a.base.blue, &b.base.blue),
+                (&a.base.magenta, &b.base.magenta),
+                (&a.base.cyan, &b.base.cyan),
+            ] {
+                assert_eq!(x.as_str(), y.as_str(), "{}", theme.name);
+            }
+        }
+    }
+}
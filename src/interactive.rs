@@ -1,4 +1,4 @@
-use crate::{cli::Args, store, targets};
+use crate::{cli::Args, osc, store, targets};
 use crossterm::{cursor, event, execute, style, terminal as term};
 use recol_lib::{self as lib, parse_theme_adjustments, Collection, ThemeAdjustment};
 use std::{
@@ -7,6 +7,8 @@ use std::{
 };
 
 const DEFAULT_SCROLLOFF: usize = 6;
+/// Number of themes listed by "more like this".
+const SIMILAR_COUNT: usize = 30;
 
 /// RAII guard that enables raw mode and the alternate screen on creation,
 /// and restores the terminal on drop.
@@ -33,6 +35,7 @@ enum Mode {
     Normal,
     Input,
     AdjustInput,
+    SaveInput,
     Help,
 }
 
@@ -57,6 +60,9 @@ struct State {
     // last_char: Option<char>,
     adjust: Vec<ThemeAdjustment>,
     adjust_input_buf: String,
+    save_input_buf: String,
+    /// Push the selected theme to the terminal through OSC on every redraw.
+    osc_preview: bool,
 }
 
 impl State {
@@ -166,6 +172,33 @@ impl State {
         self.adjust = adjust;
     }
 
+    /// Save the selected theme, with the current adjustments baked in, as a
+    /// user theme named `save_input_buf`, then select it with adjustments
+    /// cleared.
+    fn save_selected_theme(&mut self) {
+        let name = self.save_input_buf.trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let Some(mut theme) = self.list.get(self.list_index).map(|v| v.into_theme()) else {
+            return;
+        };
+        theme.colors.apply_adjustments(&self.adjust);
+        theme.name = name;
+        if store::save_user_theme(&theme).is_err() {
+            return;
+        }
+
+        self.adjust.clear();
+        self.adjust_input_buf.clear();
+        self.input_buf.clear();
+        self.reset_list();
+        self.reset_pos();
+        if let Some(i) = self.list.iter().position(|t| t.name == theme.name) {
+            self.scroll_list_down(i);
+        }
+    }
+
     #[inline]
     fn reset_pos(&mut self) {
         self.list_offset = 0;
@@ -443,6 +476,7 @@ fn draw_screen(s: &State) -> io::Result<()> {
                 &[
                     ("/ : i", "Enter input mode"),
                     ("a", "Enter adjust input mode"),
+                    ("w", "Save as new theme (adjusted)"),
                     ("Backspace", "Delete last character"),
                     ("Esc / Enter", "Exit input mode"),
                     ("f", "Filter by first word (family)"),
@@ -454,6 +488,8 @@ fn draw_screen(s: &State) -> io::Result<()> {
                     ("s / r", "Shuffle / Reverse order"),
                     ("d / l", "Dark / Light only"),
                     ("h", "Recently applied (history)"),
+                    ("m", "More like this (similar colors)"),
+                    ("p", "Live preview in the terminal (OSC)"),
                     ("Space", "Reset filters (show all)"),
                 ],
             ),
@@ -471,6 +507,7 @@ fn draw_screen(s: &State) -> io::Result<()> {
                     ("--quit-on-select", ""),
                     ("--init-input", ""),
                     ("--init-help", ""),
+                    ("--preview", ""),
                 ],
             ),
         ];
@@ -554,6 +591,9 @@ fn draw_screen(s: &State) -> io::Result<()> {
         }
         let mut preview_lines = gen_preview(&selected_theme, preview_col_width).into_iter();
         let bg = selected_theme.colors.bg.color().rgb();
+        if s.osc_preview {
+            osc::set_theme(&mut stdout, &selected_theme)?;
+        }
 
         for row in 0..s.size.1.saturating_sub(1) {
             let line = preview_lines.next().unwrap_or_default();
@@ -627,6 +667,11 @@ fn draw_screen(s: &State) -> io::Result<()> {
         write!(stdout, "Adjust:{}", s.adjust_input_buf)?;
     }
 
+    if s.mode == Mode::SaveInput {
+        execute!(stdout, cursor::MoveTo(0, s.size.1), cursor::Show)?;
+        write!(stdout, "Save as:{}", s.save_input_buf)?;
+    }
+
     if s.mode == Mode::Normal {
         let status = format!("{}/{}", s.list_index, s.list.len());
         if status.len() < s.size.0 as usize - 8 {
@@ -658,8 +703,11 @@ pub fn run(args: &Args) -> io::Result<()> {
         scrolloff: DEFAULT_SCROLLOFF,
         current_theme: store::read_theme_history(1).into_iter().next(),
         adjust: args.adjust.clone(),
+        osc_preview: args.preview,
         ..Default::default()
     };
+    // Dropping the guard restores the terminal's own colors.
+    let mut osc_guard = args.preview.then_some(osc::ResetGuard);
 
     if args.init_input {
         s.mode = Mode::Input
@@ -719,6 +767,12 @@ pub fn run(args: &Args) -> io::Result<()> {
                         s.mode = Mode::AdjustInput;
                         // s.adjust_input_buf.clear();
                     }
+                    (event::KeyCode::Char('w'), Mode::Normal) => {
+                        if let Some(theme) = s.list.get(s.list_index) {
+                            s.save_input_buf = format!("{} (adjusted)", theme.name);
+                            s.mode = Mode::SaveInput;
+                        }
+                    }
                     (event::KeyCode::Char('?' | 'H'), Mode::Normal) => {
                         s.mode = Mode::Help;
                     }
@@ -742,14 +796,14 @@ pub fn run(args: &Args) -> io::Result<()> {
                         if s.list.is_empty() {
                             s.reset_list();
                         }
-                        s.list = s.list.into_iter().filter(|t| !t.is_light).collect();
+                        s.list.retain(|t| !t.is_light);
                         s.reset_pos();
                     }
                     (event::KeyCode::Char('l'), Mode::Normal) => {
                         if s.list.is_empty() {
                             s.reset_list();
                         }
-                        s.list = s.list.into_iter().filter(|t| t.is_light).collect();
+                        s.list.retain(|t| t.is_light);
                         s.reset_pos();
                     }
                     (event::KeyCode::Char('s'), Mode::Normal) => {
@@ -766,7 +820,7 @@ pub fn run(args: &Args) -> io::Result<()> {
                             continue;
                         }
                         if let Some(theme) = s.list.get(s.list_index) {
-                            s.input_buf = theme.name.splitn(2, " ").next().unwrap_or("").into();
+                            s.input_buf = theme.name.split(" ").next().unwrap_or("").into();
                             s.reset_pos();
                             s.filter_list_by_input();
                         };
@@ -777,9 +831,7 @@ pub fn run(args: &Args) -> io::Result<()> {
                             let mut collection = Collection::new();
                             s.list = history
                                 .into_iter()
-                                .map(|t| collection.by_name(&t))
-                                .filter(Option::is_some)
-                                .map(Option::unwrap)
+                                .filter_map(|t| collection.by_name(&t))
                                 .collect();
                             if s.list.is_empty() {
                                 s.reset_list();
@@ -788,6 +840,24 @@ pub fn run(args: &Args) -> io::Result<()> {
                             s.reset_pos();
                         }
                     }
+                    (event::KeyCode::Char('m'), Mode::Normal) => {
+                        if let Some(theme) = s.list.get(s.list_index).map(|v| v.into_theme()) {
+                            // The selected theme comes first, at distance 0.
+                            s.list = Collection::new()
+                                .similar_to(&theme.colors, &[], SIMILAR_COUNT + 1)
+                                .into_iter()
+                                .map(|(t, _)| t)
+                                .collect();
+                            s.reset_pos();
+                        }
+                    }
+                    (event::KeyCode::Char('p'), Mode::Normal) => {
+                        osc_guard = match osc_guard {
+                            Some(_) => None,
+                            None => Some(osc::ResetGuard),
+                        };
+                        s.osc_preview = osc_guard.is_some();
+                    }
                     (event::KeyCode::Char(' '), Mode::Normal) => {
                         s.reset_list();
                     }
@@ -818,6 +888,21 @@ pub fn run(args: &Args) -> io::Result<()> {
                         s.process_adjust_input();
                     }
 
+                    // Save input mode
+                    (event::KeyCode::Enter, Mode::SaveInput) => {
+                        s.save_selected_theme();
+                        s.mode = Mode::Normal;
+                    }
+                    (event::KeyCode::Esc, Mode::SaveInput) => {
+                        s.mode = Mode::Normal;
+                    }
+                    (event::KeyCode::Backspace, Mode::SaveInput) => {
+                        s.save_input_buf.pop();
+                    }
+                    (event::KeyCode::Char(c), Mode::SaveInput) => {
+                        s.save_input_buf.push(c);
+                    }
+
                     // Help mode
                     (event::KeyCode::Char('q'), Mode::Help) => break,
                     (
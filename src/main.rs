@@ -1,6 +1,9 @@
 mod cli;
+mod diff;
+mod export;
 mod font;
 mod interactive;
+mod osc;
 mod store;
 mod targets;
 mod utils;
@@ -9,6 +12,12 @@ use recol_lib as lib;
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
+/// Number of themes listed by `--like`.
+const LIKE_COUNT: usize = 10;
+
+/// Theme name for `--capture` without a NAME.
+const CAPTURE_NAME: &str = "Captured";
+
 #[inline]
 fn print_theme_header(name: &str, is_light: bool) {
     println!("{name} <{}>", if is_light { "LIGHT" } else { "DARK" });
@@ -31,14 +40,250 @@ fn print_theme_as_json(name: &str, is_light: bool, colors: &lib::AdvancedColorSc
     println!("{}", json_str);
 }
 
+/// Prints the WCAG contrast report of `theme` as a table or JSON.
+fn print_contrast_report(theme: &lib::Theme, json: bool) -> Result<()> {
+    let report = theme.colors.contrast_report();
+    if json {
+        let pairs = report
+            .pairs
+            .iter()
+            .map(|p| {
+                serde_json::json!({
+                    "name": p.name,
+                    "fg": p.fg,
+                    "bg": p.bg,
+                    "ratio": (p.ratio as f64 * 100.0).round() / 100.0,
+                    "level": p.level,
+                })
+            })
+            .collect::<Vec<_>>();
+        let value = serde_json::json!({
+            "name": theme.name,
+            "is_light": theme.is_light,
+            "pairs": pairs,
+        });
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
+    print_theme_header(&theme.name, theme.is_light);
+    println!(
+        "{:<16} {:<7} {:<7} {:>6}  LEVEL",
+        "PAIR", "FG", "BG", "RATIO"
+    );
+    for p in &report.pairs {
+        let (fr, fg, fb) = p.fg.color().rgb();
+        let (br, bg, bb) = p.bg.color().rgb();
+        println!(
+            "{:<16} {} {} {:>6.2}  {:<8} \x1b[38;2;{fr};{fg};{fb};48;2;{br};{bg};{bb}m Sample \x1b[0m{}",
+            p.name,
+            p.fg,
+            p.bg,
+            p.ratio,
+            p.level,
+            if p.is_below_aa() { "  !" } else { "" },
+        );
+    }
+    let failing = report.failing().count();
+    if failing > 0 {
+        println!("{failing} of {} pairs below AA (4.5:1)", report.pairs.len());
+    }
+    Ok(())
+}
+
+/// Prints `theme` in `format`, or writes it to `output` when given.
+fn export_theme(
+    theme: &lib::Theme,
+    format: export::ExportFormat,
+    output: Option<&str>,
+) -> Result<()> {
+    let content = export::export_theme(theme, format);
+    match output {
+        Some(path) => utils::write_file(utils::expand_home(path), content)?,
+        None => print!("{content}"),
+    }
+    Ok(())
+}
+
+/// Renames `theme` to `name` and persists it as a user theme.
+fn save_theme_as(theme: &mut lib::Theme, name: &str) -> Result<()> {
+    theme.name = name.to_string();
+    store::save_user_theme(theme)?;
+    Ok(())
+}
+
+/// Blends the two themes named in `names` (fuzzy matching) at `t`.
+fn mix_themes(collection: &mut lib::Collection, names: &[String], t: f32) -> Result<lib::Theme> {
+    let [a, b] = names else {
+        return Err("--mix needs two theme names".into());
+    };
+    let mut find = |query: &str| {
+        collection
+            .fuzzy_search(query, &[], None)
+            .map(|v| v.into_theme())
+            .ok_or(format!("theme not found: {query}"))
+    };
+    let (a, b) = (find(a)?, find(b)?);
+    let name = format!("{} + {} {:.0}%", a.name, b.name, t.clamp(0.0, 1.0) * 100.0);
+    Ok(a.interpolate(&b, t, name))
+}
+
+/// `~/…` for paths under the home directory.
+fn display_path(path: &std::path::Path) -> String {
+    match std::env::home_dir().and_then(|home| path.strip_prefix(home).ok()) {
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.display().to_string(),
+    }
+}
+
+fn format_age(time: std::time::SystemTime) -> String {
+    let secs = time.elapsed().unwrap_or_default().as_secs();
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{} min ago", secs / 60),
+        3600..86400 => format!("{} h ago", secs / 3600),
+        _ => format!("{} days ago", secs / 86400),
+    }
+}
+
+fn print_restored(snapshot: &store::Snapshot) {
+    if utils::is_dry_run() {
+        return;
+    }
+    for (copy, path) in &snapshot.files {
+        let action = if copy.is_some() {
+            "restored"
+        } else {
+            "removed"
+        };
+        println!("{action} {}", display_path(path));
+    }
+}
+
+/// Prints what `--dry-run` would have written, as a unified diff per file.
+fn print_dry_run_diffs() {
+    for (path, content) in utils::take_dry_run_writes() {
+        let old = std::fs::read_to_string(&path).ok();
+        let new = content.map(|c| String::from_utf8_lossy(&c).into_owned());
+        let path = path.display().to_string();
+        if let Some(diff) = diff::unified(&path, old.as_deref(), new.as_deref()) {
+            print!("{diff}");
+        }
+    }
+}
+
 fn main() -> Result<()> {
     let args = cli::Args::parse();
+    if args.dry_run {
+        utils::enable_dry_run();
+    }
 
     store::init();
+    let _ = lib::load_user_themes(store::user_themes_dir());
+
+    if args.undo {
+        let snapshot = store::list_snapshots()
+            .into_iter()
+            .next()
+            .ok_or("nothing to undo")?;
+        print_restored(&snapshot);
+        snapshot.undo()?;
+        print_dry_run_diffs();
+        return Ok(());
+    }
+
+    if args.backups {
+        let snapshots = store::list_snapshots();
+        if let Some(n) = args.restore_backup {
+            let snapshot = n
+                .checked_sub(1)
+                .and_then(|i| snapshots.get(i))
+                .ok_or(format!("no backup {n}"))?;
+            // Back up the current state first, so --undo reverts the restore.
+            for (_, path) in &snapshot.files {
+                store::backup(path)?;
+            }
+            snapshot.restore()?;
+            print_restored(snapshot);
+        } else {
+            for (i, snapshot) in snapshots.iter().enumerate() {
+                println!("{:>2}  {}", i + 1, format_age(snapshot.time));
+                for (copy, path) in &snapshot.files {
+                    let created = if copy.is_none() { " (created)" } else { "" };
+                    println!("      {}{created}", display_path(path));
+                }
+            }
+        }
+        print_dry_run_diffs();
+        return Ok(());
+    }
+
+    if let Some(ref path) = args.import {
+        let mut theme = lib::import_theme(path, None)?;
+        if !args.adjust.is_empty() {
+            theme.colors.apply_adjustments(&args.adjust);
+        }
+        if let Some(ref name) = args.save_as {
+            theme.name = name.clone();
+        }
+        store::save_user_theme(&theme)?;
+        if args.json {
+            print_theme_as_json(
+                &theme.name,
+                theme.is_light,
+                &theme.colors.into_advanced(None),
+            );
+        } else {
+            print_theme_header(&theme.name, theme.is_light);
+            if args.show {
+                theme.print_palette();
+            }
+        }
+        print_dry_run_diffs();
+        return Ok(());
+    }
 
     let mut collection = lib::Collection::new();
 
+    if let Some(ref query) = args.like {
+        let theme = collection
+            .fuzzy_search(query, &[], None)
+            .map(|v| v.into_theme())
+            .ok_or(format!("theme not found: {query}"))?;
+        let similar = collection
+            .similar_to(&theme.colors, &args.theme_filters(), LIKE_COUNT + 1)
+            .into_iter()
+            .filter(|(t, _)| t.name != theme.name)
+            .take(LIKE_COUNT);
+        if args.json {
+            let json_list = similar
+                .map(|(t, _)| {
+                    theme_as_json(
+                        t.name,
+                        t.is_light,
+                        &t.into_theme().colors.into_advanced(None),
+                    )
+                })
+                .collect::<Vec<_>>();
+            println!("{}", serde_json::to_string_pretty(&json_list)?);
+        } else {
+            for (t, distance) in similar {
+                if args.show {
+                    print_theme_header(t.name, t.is_light);
+                    t.into_theme().print_palette();
+                } else {
+                    println!("{distance:>5.1}  {}", t.name);
+                }
+            }
+        }
+        return Ok(());
+    }
+
     if args.theme.is_none()
+        && args.generate.is_none()
+        && args.from_image.is_none()
+        && args.capture.is_none()
+        && args.mix.is_empty()
         && !args.rand
         && args.contains.is_none()
         && !args.theme_list
@@ -54,9 +299,19 @@ fn main() -> Result<()> {
             let mut theme = lazy_theme.into_theme();
             if !args.adjust.is_empty() {
                 theme.colors.apply_adjustments(&args.adjust);
+            }
+            if let Some(ref name) = args.save_as {
+                save_theme_as(&mut theme, name)?;
+                store::append_theme_history(&theme.name);
+            }
+            if !args.adjust.is_empty() && args.export.is_none() && !args.audit {
                 targets::apply_theme(&args, &theme)?;
             }
-            if args.show {
+            if let Some(format) = args.export {
+                export_theme(&theme, format, args.output.as_deref())?;
+            } else if args.audit {
+                print_contrast_report(&theme, args.json)?;
+            } else if args.show {
                 print_theme_header(&theme.name, theme.is_light);
                 theme.print_palette();
             } else if args.json {
@@ -69,15 +324,50 @@ fn main() -> Result<()> {
                 print_theme_header(&theme.name, theme.is_light);
             }
         }
+        print_dry_run_diffs();
         return Ok(());
     }
 
-    if args.theme.is_some() || args.rand || args.theme_list {
+    if args.theme.is_some()
+        || args.generate.is_some()
+        || args.from_image.is_some()
+        || args.capture.is_some()
+        || !args.mix.is_empty()
+        || args.rand
+        || args.theme_list
+    {
         let filters = args.theme_filters();
 
         let mut theme = None;
 
-        if let Some(ref query) = args.theme {
+        if !args.mix.is_empty() {
+            theme = Some(mix_themes(
+                &mut collection,
+                &args.mix,
+                args.mix_ratio.unwrap_or(0.5),
+            )?);
+        } else if let Some(ref path) = args.from_image {
+            let name = std::path::Path::new(path)
+                .file_stem()
+                .and_then(|n| n.to_str())
+                .unwrap_or("Image");
+            let is_light = (args.light || args.dark).then_some(args.light);
+            let pixels = utils::read_image_pixels(path)?;
+            theme = Some(
+                lib::Theme::from_pixels(&pixels, name, is_light)
+                    .ok_or(format!("no usable pixels in image: {path}"))?,
+            );
+        } else if let Some(ref name) = args.capture {
+            let name = if name.is_empty() { CAPTURE_NAME } else { name };
+            theme = Some(osc::capture(name)?);
+        } else if let Some(ref seed) = args.generate {
+            let accent = lib::parse_color_value(seed).ok_or(format!("invalid color: {seed}"))?;
+            theme = Some(lib::Theme::from_seed(
+                accent,
+                args.light,
+                lib::SeedOptions::default(),
+            ));
+        } else if let Some(ref query) = args.theme {
             theme = theme.or(collection
                 .fuzzy_search(query, &filters, None)
                 .map(|v| v.into_theme()));
@@ -99,24 +389,29 @@ fn main() -> Result<()> {
             if !args.adjust.is_empty() {
                 theme.colors.apply_adjustments(&args.adjust);
             }
-            loop {
-                if args.show {
-                    print_theme_header(&theme.name, theme.is_light);
-                    theme.print_palette();
-                    break;
-                }
-                if args.json {
-                    print_theme_as_json(
-                        &theme.name,
-                        theme.is_light,
-                        &theme.colors.clone().into_advanced(None),
-                    );
-                    break;
-                }
+            if let Some(ref name) = args.save_as {
+                save_theme_as(theme, name)?;
+            }
+            if let Some(format) = args.export {
+                export_theme(theme, format, args.output.as_deref())?;
+            } else if args.audit {
+                print_contrast_report(theme, args.json)?;
+            } else if args.show {
+                print_theme_header(&theme.name, theme.is_light);
+                theme.print_palette();
+            } else if args.json {
+                print_theme_as_json(
+                    &theme.name,
+                    theme.is_light,
+                    &theme.colors.clone().into_advanced(None),
+                );
+            } else if args.preview {
+                print_theme_header(&theme.name, theme.is_light);
+                osc::preview(theme)?;
+            } else {
                 print_theme_header(&theme.name, theme.is_light);
                 targets::apply_theme(&args, theme)?;
                 store::append_theme_history(&theme.name);
-                break;
             }
         }
 
@@ -124,7 +419,6 @@ fn main() -> Result<()> {
             if theme.is_none() && args.json {
                 let json_list = collection
                     .filtered(&filters)
-                    .into_iter()
                     .map(|v| {
                         theme_as_json(
                             v.name,
@@ -192,5 +486,6 @@ fn main() -> Result<()> {
         interactive::run(&args)?;
     }
 
+    print_dry_run_diffs();
     Ok(())
 }
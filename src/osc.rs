@@ -0,0 +1,197 @@
+//! Live preview through OSC escape sequences: the running terminal is
+//! recolored directly, no config is touched.
+//!
+//! ```text
+//! OSC 4    palette, indices 0–255 (same colors the config writers use)
+//! OSC 10   foreground        OSC 110 resets
+//! OSC 11   background        OSC 111 resets
+//! OSC 12   cursor            OSC 112 resets
+//! OSC 17   selection bg      OSC 117 resets
+//! OSC 19   selection fg      OSC 119 resets
+//!                            OSC 104 resets the palette
+//! ```
+//!
+//! The same codes with `?` as the value query the current colors, which is
+//! how [`capture`] reads a theme back from the terminal.
+
+use crate::targets;
+use crossterm::{event, terminal as term};
+use recol_lib as lib;
+use std::io::{self, IsTerminal, Write};
+#[cfg(unix)]
+use std::time::{Duration, Instant};
+
+/// String terminator; more widely understood than BEL.
+const ST: &str = "\x1b\\";
+
+/// Primary device attributes request. Every terminal answers it, so its
+/// reply marks the end of the OSC replies, including for terminals that
+/// ignore some of the queries.
+#[cfg(unix)]
+const DA1: &str = "\x1b[c";
+
+/// How long [`capture`] waits for the terminal to answer.
+#[cfg(unix)]
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(2);
+
+fn osc_color(c: &lib::Color) -> String {
+    let (r, g, b) = c.rgb();
+    format!("rgb:{r:02x}/{g:02x}/{b:02x}")
+}
+
+/// Push `theme` to the terminal behind `out`.
+pub fn set_theme(out: &mut impl Write, theme: &lib::Theme) -> io::Result<()> {
+    let c = &theme.colors;
+    let mut seq = String::new();
+
+    let ansi = [&c.base, &c.bright].map(|a| {
+        [
+            &a.black, &a.red, &a.green, &a.yellow, &a.blue, &a.magenta, &a.cyan, &a.white,
+        ]
+    });
+    for (i, color) in ansi.iter().flatten().enumerate() {
+        seq += &format!("\x1b]4;{i};{}{ST}", osc_color(&color.color()));
+    }
+    for (i, color) in targets::indexed_colors(c) {
+        seq += &format!("\x1b]4;{i};{}{ST}", osc_color(&color.color()));
+    }
+
+    for (code, color) in [
+        (10, &c.fg),
+        (11, &c.bg),
+        (12, &c.cursor.bg),
+        (17, &c.selection.bg),
+        (19, &c.selection.fg),
+    ] {
+        seq += &format!("\x1b]{code};{}{ST}", osc_color(&color.color()));
+    }
+
+    out.write_all(seq.as_bytes())?;
+    out.flush()
+}
+
+/// Restore the colors from the terminal's own config.
+pub fn reset(out: &mut impl Write) -> io::Result<()> {
+    for code in [104, 110, 111, 112, 117, 119] {
+        write!(out, "\x1b]{code}{ST}")?;
+    }
+    out.flush()
+}
+
+/// Calls [`reset`] on drop, so the terminal gets its colors back however
+/// the preview ends.
+pub struct ResetGuard;
+
+impl Drop for ResetGuard {
+    fn drop(&mut self) {
+        let _ = reset(&mut io::stdout());
+    }
+}
+
+/// Show `theme` in the current terminal until a key is pressed, then
+/// restore the original colors.
+pub fn preview(theme: &lib::Theme) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    if !stdout.is_terminal() {
+        return Err(io::Error::other("--preview needs a terminal on stdout"));
+    }
+
+    let _reset_guard = ResetGuard;
+    set_theme(&mut stdout, theme)?;
+    println!("Press any key to restore colors");
+
+    // Raw mode so that Ctrl-C also comes in as a key instead of killing the
+    // process with the preview still applied.
+    term::enable_raw_mode()?;
+    let result = loop {
+        match event::read() {
+            Ok(event::Event::Key(key)) if key.kind == event::KeyEventKind::Press => break Ok(()),
+            Ok(_) => {}
+            Err(e) => break Err(e),
+        }
+    };
+    let _ = term::disable_raw_mode();
+    result
+}
+
+/// Read the running terminal's colors back as a theme named `name`.
+#[cfg(unix)]
+pub fn capture(name: &str) -> io::Result<lib::Theme> {
+    let mut stdout = io::stdout();
+    if !stdout.is_terminal() || !io::stdin().is_terminal() {
+        return Err(io::Error::other("--capture needs a terminal"));
+    }
+
+    let mut query = (0..16).fold(String::new(), |q, i| q + &format!("\x1b]4;{i};?{ST}"));
+    for code in [10, 11, 12, 17, 19] {
+        query += &format!("\x1b]{code};?{ST}");
+    }
+    query += DA1;
+
+    term::enable_raw_mode()?;
+    let replies = stdout
+        .write_all(query.as_bytes())
+        .and_then(|_| stdout.flush())
+        .and_then(|_| read_until_da1());
+    let _ = term::disable_raw_mode();
+
+    lib::parse_osc_replies(&replies?, name)
+}
+
+#[cfg(not(unix))]
+pub fn capture(_name: &str) -> io::Result<lib::Theme> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "--capture is not supported on this platform",
+    ))
+}
+
+/// Collect stdin until the [`DA1`] reply (`ESC[?…c`) arrives. Each read
+/// waits in `poll(2)` for at most the time left, so a silent terminal ends
+/// in a timeout instead of a hang. The fd is read directly, as buffered
+/// input would hide pending bytes from `poll`.
+#[cfg(unix)]
+fn read_until_da1() -> io::Result<String> {
+    use std::os::fd::AsRawFd;
+
+    let fd = io::stdin().as_raw_fd();
+    let deadline = Instant::now() + CAPTURE_TIMEOUT;
+    let mut replies = String::new();
+    let mut buf = [0u8; 1024];
+    loop {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let mut pollfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `pollfd` is a single valid entry that outlives the call.
+        let ready = unsafe { libc::poll(&mut pollfd, 1, timeout.as_millis() as libc::c_int) };
+        match ready {
+            0 => {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "no reply from the terminal",
+                ))
+            }
+            ..0 => match io::Error::last_os_error() {
+                e if e.kind() == io::ErrorKind::Interrupted => continue,
+                e => return Err(e),
+            },
+            _ => {}
+        }
+
+        // SAFETY: `buf` is valid for writes of `buf.len()` bytes.
+        let n = unsafe { libc::read(fd, buf.as_mut_ptr().cast(), buf.len()) };
+        let n = match usize::try_from(n) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(n) => n,
+            Err(_) => return Err(io::Error::last_os_error()),
+        };
+        replies += &String::from_utf8_lossy(&buf[..n]);
+        let da1 = replies.rfind("\x1b[?").map(|i| &replies[i..]);
+        if da1.is_some_and(|r| r.contains('c')) {
+            return Ok(replies);
+        }
+    }
+}
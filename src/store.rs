@@ -1,14 +1,23 @@
+use crate::utils;
+use recol_lib as lib;
 use std::{
     env, fs,
-    io::{BufRead, BufReader, BufWriter, Write},
-    path::PathBuf,
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 const APP_STATE_DIRNAME: &str = "recol";
+const APP_CONFIG_DIRNAME: &str = "recol";
+const USER_THEMES_DIRNAME: &str = "themes";
 const THEME_HISTORY_FILE: &str = "theme.history";
 const FONT_HISTORY_FILE: &str = "font.history";
+const BACKUPS_DIRNAME: &str = "backups";
+const BACKUP_MANIFEST_FILE: &str = "manifest";
 pub const THEME_HISTORY_CAP: usize = 128;
 pub const FONT_HISTORY_CAP: usize = 16;
+pub const BACKUPS_CAP: usize = 32;
 
 /// Resolves the app's state directory per the XDG Base Directory spec:
 /// `$XDG_STATE_HOME/recol`, falling back to `$HOME/.local/state/recol`,
@@ -16,12 +25,48 @@ pub const FONT_HISTORY_CAP: usize = 16;
 fn store_dir() -> PathBuf {
     let base = env::var_os("XDG_STATE_HOME")
         .map(PathBuf::from)
-        .or_else(|| env::home_dir().map(|home| PathBuf::from(home).join(".local/state")))
+        .or_else(|| env::home_dir().map(|home| home.join(".local/state")))
         .unwrap_or_else(env::temp_dir);
 
     base.join(APP_STATE_DIRNAME)
 }
 
+/// Directory holding user themes: `$XDG_CONFIG_HOME/recol/themes`,
+/// falling back to `$HOME/.config/recol/themes`.
+pub fn user_themes_dir() -> PathBuf {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::home_dir().map(|home| home.join(".config")))
+        .unwrap_or_else(env::temp_dir);
+
+    base.join(APP_CONFIG_DIRNAME).join(USER_THEMES_DIRNAME)
+}
+
+/// Writes `theme` as recol JSON into [`user_themes_dir`] and registers it
+/// with the collection. Returns the path of the written file.
+pub fn save_user_theme(theme: &lib::Theme) -> io::Result<PathBuf> {
+    let file_name: String = theme
+        .name
+        .trim_start_matches('.')
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\') { '_' } else { c })
+        .collect();
+    if file_name.trim().is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "theme name is empty",
+        ));
+    }
+
+    lib::add_user_theme(theme)?;
+
+    let path = user_themes_dir().join(format!("{file_name}.json"));
+    let content = serde_json::to_string_pretty(theme).map_err(io::Error::other)?;
+    utils::write_file(&path, content)?;
+
+    Ok(path)
+}
+
 /// Initializes the storage directory. Safe to call multiple times.
 #[inline(always)]
 pub fn init() {
@@ -44,6 +89,9 @@ fn read_history(file_name: &str, limit: usize) -> Vec<String> {
 /// Prepends `entry` to `file_name`, keeping at most `cap` previous entries.
 /// Skips writing if `entry` is identical to the most recent (top) entry.
 fn append_history(file_name: &str, entry: &str, cap: usize) {
+    if utils::is_dry_run() {
+        return;
+    }
     let previous = read_history(file_name, cap);
 
     if previous.first().map(String::as_str) == Some(entry) {
@@ -81,3 +129,126 @@ pub fn read_font_history(limit: usize) -> Vec<String> {
 pub fn append_font_history(font_name: &str) {
     append_history(FONT_HISTORY_FILE, font_name, FONT_HISTORY_CAP);
 }
+
+/// One run's backups: the original contents of every file a writer touched,
+/// taken before the first write.
+///
+/// ```text
+/// backups/<unix ms>-<pid>/
+///     manifest    one "<copy>\t<path>" line per file; copy "-" marks a
+///                 file the run created, which restoring deletes
+///     0, 1, …     copies
+/// ```
+pub struct Snapshot {
+    dir: PathBuf,
+    pub time: SystemTime,
+    /// `(copy, original path)`; no copy for files the run created.
+    pub files: Vec<(Option<PathBuf>, PathBuf)>,
+}
+
+fn backups_dir() -> PathBuf {
+    store_dir().join(BACKUPS_DIRNAME)
+}
+
+/// This run's snapshot dir, created on first use.
+fn current_snapshot_dir() -> io::Result<&'static Path> {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    if let Some(dir) = DIR.get() {
+        return Ok(dir);
+    }
+
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let dir = backups_dir().join(format!("{millis:013}-{}", std::process::id()));
+    fs::create_dir_all(&dir)?;
+    for old in list_snapshots().into_iter().skip(BACKUPS_CAP) {
+        let _ = fs::remove_dir_all(old.dir);
+    }
+    Ok(DIR.get_or_init(|| dir))
+}
+
+/// Records `path` in this run's snapshot before a writer changes it. Only
+/// the first call per path copies anything, so the snapshot keeps the state
+/// from before the run.
+pub fn backup(path: impl AsRef<Path>) -> io::Result<()> {
+    if utils::is_dry_run() {
+        return Ok(());
+    }
+    let path = std::path::absolute(path)?;
+    let dir = current_snapshot_dir()?;
+    let manifest_path = dir.join(BACKUP_MANIFEST_FILE);
+    let entries = read_manifest(dir);
+    if entries.iter().any(|(_, p)| *p == path) {
+        return Ok(());
+    }
+
+    let copy = if path.is_file() {
+        let copy = entries.len().to_string();
+        fs::copy(&path, dir.join(&copy))?;
+        copy
+    } else {
+        "-".to_string()
+    };
+    let mut manifest = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(manifest_path)?;
+    writeln!(manifest, "{copy}\t{}", path.display())
+}
+
+fn read_manifest(dir: &Path) -> Vec<(Option<PathBuf>, PathBuf)> {
+    fs::read_to_string(dir.join(BACKUP_MANIFEST_FILE))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|l| l.split_once('\t'))
+        .map(|(copy, path)| ((copy != "-").then(|| dir.join(copy)), PathBuf::from(path)))
+        .collect()
+}
+
+/// Snapshots with at least one file, newest first.
+pub fn list_snapshots() -> Vec<Snapshot> {
+    let Ok(entries) = fs::read_dir(backups_dir()) else {
+        return Vec::new();
+    };
+    let mut snapshots = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let dir = e.path();
+            let millis = e.file_name().to_str()?.split('-').next()?.parse().ok()?;
+            let files = read_manifest(&dir);
+            (!files.is_empty()).then(|| Snapshot {
+                time: UNIX_EPOCH + Duration::from_millis(millis),
+                files,
+                dir,
+            })
+        })
+        .collect::<Vec<_>>();
+    snapshots.sort_by(|a, b| b.dir.cmp(&a.dir));
+    snapshots
+}
+
+impl Snapshot {
+    /// Puts every file back as it was before the snapshot's run.
+    pub fn restore(&self) -> io::Result<()> {
+        for (copy, path) in &self.files {
+            match copy {
+                Some(copy) => utils::replace_file(path, fs::read(copy)?)?,
+                None if path.is_file() => utils::remove_file(path)?,
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Restores the snapshot and deletes it, so the next undo goes one run
+    /// further back. Under `--dry-run` the snapshot is kept.
+    pub fn undo(self) -> io::Result<()> {
+        self.restore()?;
+        if utils::is_dry_run() {
+            return Ok(());
+        }
+        fs::remove_dir_all(self.dir)
+    }
+}
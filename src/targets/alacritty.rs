@@ -1,17 +1,16 @@
 use crate::utils;
 use recol_lib as lib;
 use serde::{Deserialize, Serialize};
-use std::{fs, io, path::Path};
+use std::{io, path::Path};
 
 fn write_config(path: impl AsRef<Path>, c: &Config) -> io::Result<()> {
     let content = toml::to_string::<Config>(c).map_err(|_| utils::io_other_error("serde fail"))?;
 
-    fs::write(&path, &content)?;
-    Ok(())
+    utils::write_file(path, content)
 }
 
 pub fn write_theme_to_config(path: impl AsRef<Path>, theme: &lib::Theme) -> io::Result<()> {
-    let content = fs::read_to_string(&path)?;
+    let content = utils::read_to_string(&path)?;
     let mut config =
         toml::from_str::<Config>(&content).map_err(|_| utils::io_other_error("serde fail"))?;
     config
@@ -22,7 +21,7 @@ pub fn write_theme_to_config(path: impl AsRef<Path>, theme: &lib::Theme) -> io::
 }
 
 pub fn set_font_to_config(path: impl AsRef<Path>, font: String) -> io::Result<()> {
-    let content = fs::read_to_string(&path)?;
+    let content = utils::read_to_string(&path)?;
     let mut config =
         toml::from_str::<Config>(&content).map_err(|_| utils::io_other_error("serde fail"))?;
     config.set_font_family(font);
@@ -87,6 +86,12 @@ pub struct Colors {
 
 impl Colors {
     pub fn from_color_scheme(cs: &lib::ColorScheme) -> Self {
+        let indexed_colors = super::indexed_colors(cs)
+            .map(|(index, color)| IndexedColor {
+                index: index as u8,
+                color: color.to_string(),
+            })
+            .collect();
         let cs = cs
             .clone()
             .into_advanced(None);
@@ -105,16 +110,7 @@ impl Colors {
                 background: Some(cs.selection.bg.into()),
                 text: Some(cs.selection.fg.into()),
             }),
-            indexed_colors: vec![
-                IndexedColor {
-                    index: 16,
-                    color: cs.base.orange.to_string(),
-                },
-                IndexedColor {
-                    index: 17,
-                    color: cs.base.pink.to_string(),
-                },
-            ],
+            indexed_colors,
             normal: Some(AnsiColors::from_color_scheme_ansi(cs.base)),
             bright: Some(AnsiColors::from_color_scheme_ansi(cs.bright)),
             dim: Some(AnsiColors::from_color_scheme_ansi(cs.dim)),
@@ -0,0 +1,230 @@
+use crate::utils;
+use recol_lib as lib;
+use std::{io, path::Path};
+
+/// One line of `foot.ini`: its original text, kept verbatim unless the
+/// line is one recol sets, and what it parsed as.
+#[derive(Debug)]
+struct ConfigLine {
+    text: String,
+    kind: LineKind,
+}
+
+#[derive(Debug)]
+enum LineKind {
+    Section(String),
+    Key(String),
+    Empty,
+    Other,
+}
+
+impl ConfigLine {
+    fn section(name: &str) -> Self {
+        Self {
+            text: format!("[{name}]"),
+            kind: LineKind::Section(name.into()),
+        }
+    }
+
+    fn key_value(k: &str, v: &str) -> Self {
+        Self {
+            text: format!("{k}={v}"),
+            kind: LineKind::Key(k.into()),
+        }
+    }
+
+    fn value(&self) -> Option<&str> {
+        self.text.split_once('=').map(|(_, v)| v.trim())
+    }
+
+    /// Replace the value, keeping the key and spacing as written.
+    fn set_value(&mut self, v: &str) {
+        let Some(eq) = self.text.find('=') else {
+            return;
+        };
+        let rest = &self.text[eq + 1..];
+        let prefix_len = eq + 1 + rest.len() - rest.trim_start().len();
+        self.text.truncate(prefix_len);
+        self.text.push_str(v);
+    }
+}
+
+fn read_config(path: impl AsRef<Path>) -> io::Result<Vec<ConfigLine>> {
+    Ok(parse_config(&utils::read_to_string(path)?))
+}
+
+fn parse_config(content: &str) -> Vec<ConfigLine> {
+    let mut lines = Vec::with_capacity(32);
+    for text in content.lines() {
+        let line = text.trim();
+        let kind = if line.is_empty() {
+            LineKind::Empty
+        } else if line.starts_with('#') || line.starts_with(';') {
+            LineKind::Other
+        } else if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            LineKind::Section(name.trim().into())
+        } else if let Some((k, _)) = line.split_once('=') {
+            LineKind::Key(k.trim().into())
+        } else {
+            LineKind::Other
+        };
+        lines.push(ConfigLine {
+            text: text.into(),
+            kind,
+        });
+    }
+
+    lines
+}
+
+fn render_config(lines: &[ConfigLine]) -> String {
+    lines
+        .iter()
+        .fold(String::new(), |out, l| out + &l.text + "\n")
+}
+
+fn write_config(path: impl AsRef<Path>, lines: &[ConfigLine]) -> io::Result<()> {
+    utils::write_file(path, render_config(lines))
+}
+
+/// Returns the line range `[start, end)` of the body of `section`.
+///
+/// `"main"` also matches the implicit section before the first header.
+/// Returns `None` when the section does not exist.
+fn section_range(lines: &[ConfigLine], section: &str) -> Option<(usize, usize)> {
+    let mut current = "main";
+    let mut start = (section == "main").then_some(0);
+
+    for (i, line) in lines.iter().enumerate() {
+        if let LineKind::Section(name) = &line.kind {
+            if let Some(start) = start {
+                if current == section && name != section {
+                    return Some((start, i));
+                }
+            }
+            current = name;
+            if current == section {
+                start.get_or_insert(i + 1);
+            }
+        }
+    }
+
+    start.map(|start| (start, lines.len()))
+}
+
+fn replace_or_add_key_value(lines: &mut Vec<ConfigLine>, section: &str, k: &str, v: String) {
+    let Some((start, mut end)) = section_range(lines, section) else {
+        if lines
+            .last()
+            .is_some_and(|l| !matches!(l.kind, LineKind::Empty))
+        {
+            lines.push(ConfigLine {
+                text: String::new(),
+                kind: LineKind::Empty,
+            });
+        }
+        lines.push(ConfigLine::section(section));
+        lines.push(ConfigLine::key_value(k, &v));
+        return;
+    };
+
+    if let Some(line) = lines[start..end]
+        .iter_mut()
+        .rev()
+        .find(|l| matches!(&l.kind, LineKind::Key(rk) if rk == k))
+    {
+        line.set_value(&v);
+        return;
+    }
+
+    // Keep blank lines separating sections below the inserted key.
+    while end > start && matches!(lines[end - 1].kind, LineKind::Empty) {
+        end -= 1;
+    }
+    lines.insert(end, ConfigLine::key_value(k, &v));
+}
+
+/// Foot expects colors as bare `rrggbb` without the leading `#`.
+#[inline(always)]
+fn hex(c: &lib::CssColor) -> String {
+    c.as_str().trim_start_matches('#').to_string()
+}
+
+fn set_theme_colors(lines: &mut Vec<ConfigLine>, theme: &lib::Theme) {
+    let c = &theme.colors;
+
+    let mut set = |k: &str, v: String| replace_or_add_key_value(lines, "colors", k, v);
+
+    set("background", hex(&c.bg));
+    set("foreground", hex(&c.fg));
+    set("selection-background", hex(&c.selection.bg));
+    set("selection-foreground", hex(&c.selection.fg));
+    set(
+        "cursor",
+        format!("{} {}", hex(&c.cursor.fg), hex(&c.cursor.bg)),
+    );
+
+    for (prefix, ansi) in [("regular", &c.base), ("bright", &c.bright)] {
+        let colors = [
+            &ansi.black,
+            &ansi.red,
+            &ansi.green,
+            &ansi.yellow,
+            &ansi.blue,
+            &ansi.magenta,
+            &ansi.cyan,
+            &ansi.white,
+        ];
+        for (i, color) in colors.into_iter().enumerate() {
+            set(&format!("{prefix}{i}"), hex(color));
+        }
+    }
+}
+
+pub fn write_theme_to_config(path: impl AsRef<Path>, theme: &lib::Theme) -> io::Result<()> {
+    let mut lines = read_config(&path)?;
+    set_theme_colors(&mut lines, theme);
+    write_config(path, &lines)
+}
+
+pub fn set_font_to_config(path: impl AsRef<Path>, font: String) -> io::Result<()> {
+    let mut lines = read_config(&path)?;
+
+    // Keep attributes such as `:size=11` from the current value.
+    let attrs = section_range(&lines, "main").and_then(|(start, end)| {
+        lines[start..end].iter().find_map(|l| match &l.kind {
+            LineKind::Key(k) if k == "font" => l
+                .value()
+                .and_then(|v| v.split_once(':'))
+                .map(|(_, a)| a.to_string()),
+            _ => None,
+        })
+    });
+    let font = match attrs {
+        Some(attrs) => format!("{font}:{attrs}"),
+        None => font,
+    };
+
+    replace_or_add_key_value(&mut lines, "main", "font", font);
+
+    write_config(path, &lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn untouched_sections_keep_their_text() {
+        let kept = "[main]\nfont = Iosevka:size=11\n\n[scrollback]\n  lines = 1000\n# note\n\n";
+        let config = format!("{kept}[colors]\nbackground = 000000\n");
+        let theme = lib::Collection::new().by_name("Nord").unwrap().into_theme();
+
+        let mut lines = parse_config(&config);
+        set_theme_colors(&mut lines, &theme);
+        let out = render_config(&lines);
+
+        assert!(out.starts_with(&format!("{kept}[colors]\nbackground = 2e3440\n")));
+        assert!(out.contains("\nregular0="));
+    }
+}
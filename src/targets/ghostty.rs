@@ -1,7 +1,7 @@
+use crate::utils;
 use recol_lib as lib;
 use std::{
-    fs,
-    io::{self, BufRead, BufWriter, Write},
+    io::{self, BufRead, Write},
     path::Path,
 };
 
@@ -15,8 +15,8 @@ enum ConfigLine {
 }
 
 fn read_config(path: impl AsRef<Path>) -> io::Result<Vec<ConfigLine>> {
-    let file = fs::File::open(path)?;
-    let reader = io::BufReader::new(file);
+    let content = utils::read_to_string(path)?;
+    let reader = content.as_bytes();
 
     let mut lines = Vec::with_capacity(32);
     for line in reader.lines() {
@@ -48,8 +48,7 @@ fn read_config(path: impl AsRef<Path>) -> io::Result<Vec<ConfigLine>> {
 }
 
 fn write_config(path: impl AsRef<Path>, lines: &[ConfigLine]) -> io::Result<()> {
-    let file = fs::File::create(path)?;
-    let mut writer = BufWriter::new(file);
+    let mut writer = Vec::new();
 
     for line in lines {
         match line {
@@ -57,12 +56,11 @@ fn write_config(path: impl AsRef<Path>, lines: &[ConfigLine]) -> io::Result<()>
             ConfigLine::Palette((n, c)) => writeln!(writer, "palette = {n}={c}")?,
             ConfigLine::Comment(c) => writeln!(writer, "{}", c)?,
             ConfigLine::Any(l) => writeln!(writer, "{}", l)?,
-            ConfigLine::Empty => writeln!(writer, "")?,
+            ConfigLine::Empty => writeln!(writer)?,
         }
     }
 
-    writer.flush()?;
-    Ok(())
+    utils::write_file(path, writer)
 }
 
 #[inline(always)]
@@ -87,9 +85,9 @@ fn replace_or_add_key_value(lines: &mut Vec<ConfigLine>, k: &str, v: String) {
         }
         false
     }) {
-        *rv = v.into();
+        *rv = v;
     } else {
-        lines.push(ConfigLine::KeyValue((k.into(), v.into())));
+        lines.push(ConfigLine::KeyValue((k.into(), v)));
     };
 }
 
@@ -114,8 +112,9 @@ pub fn write_theme_to_config(path: impl AsRef<Path>, theme: &lib::Theme) -> io::
     replace_or_add_palette(&mut lines, 14, theme.colors.bright.cyan.to_string());
     replace_or_add_palette(&mut lines, 15, theme.colors.bright.white.to_string());
 
-    replace_or_add_palette(&mut lines, 16, theme.colors.base.orange.to_string());
-    replace_or_add_palette(&mut lines, 17, theme.colors.base.pink.to_string());
+    for (i, color) in super::indexed_colors(&theme.colors) {
+        replace_or_add_palette(&mut lines, i as isize, color.to_string());
+    }
 
     replace_or_add_key_value(&mut lines, "background", theme.colors.bg.to_string());
     replace_or_add_key_value(&mut lines, "foreground", theme.colors.fg.to_string());
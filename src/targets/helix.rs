@@ -0,0 +1,246 @@
+use crate::utils;
+use recol_lib as lib;
+use std::{
+    io::{self, BufRead},
+    path::Path,
+};
+
+const THEME_NAME: &str = "recol";
+
+/// Points `theme` at the generated theme, keeping the rest of the config
+/// (including comments) intact. Only the top-level key is touched.
+fn set_theme_in_config(path: &Path) -> io::Result<()> {
+    let content = utils::read_to_string(path)?;
+    let reader = content.as_bytes();
+
+    let mut lines = Vec::new();
+    let mut replaced = false;
+    let mut top_level = true;
+
+    for line in reader.lines() {
+        let line = line?;
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('[') {
+            if !replaced {
+                lines.push(format!("theme = \"{THEME_NAME}\""));
+                replaced = true;
+            }
+            top_level = false;
+        }
+        if top_level
+            && trimmed
+                .split_once('=')
+                .is_some_and(|(k, _)| k.trim() == "theme")
+        {
+            if !replaced {
+                lines.push(format!("theme = \"{THEME_NAME}\""));
+                replaced = true;
+            }
+            continue;
+        }
+        lines.push(line);
+    }
+    if !replaced {
+        lines.push(format!("theme = \"{THEME_NAME}\""));
+    }
+
+    utils::write_file(path, lines.join("\n") + "\n")
+}
+
+pub fn write_theme_to_config(path: impl AsRef<Path>, theme: &lib::Theme) -> io::Result<()> {
+    let path = path.as_ref();
+    let c = theme.colors.clone().into_advanced(None);
+    let i = if theme.is_light { "dim" } else { "bright" };
+
+    let content = format!(
+        r###"# {theme_name}
+# Generated by recol. Changes will be overwritten.
+
+"attribute" = "orange_{i}"
+"comment" = {{ fg = "comment", modifiers = ["italic"] }}
+"constant" = "orange_{i}"
+"constant.builtin" = "orange_{i}"
+"constant.character.escape" = {{ fg = "yellow_{i}", modifiers = ["bold"] }}
+"constant.numeric" = "orange"
+"constructor" = "cyan"
+"function" = "blue_{i}"
+"function.builtin" = "red"
+"function.macro" = "red"
+"keyword" = "magenta"
+"keyword.control.conditional" = "magenta_{i}"
+"keyword.control.repeat" = "magenta_{i}"
+"keyword.control.return" = "red"
+"keyword.directive" = "pink_{i}"
+"keyword.operator" = "fg2"
+"label" = "magenta_{i}"
+"namespace" = "cyan_{i}"
+"operator" = "fg2"
+"punctuation" = "fg2"
+"punctuation.special" = "cyan_{i}"
+"special" = "blue_{i}"
+"string" = "green"
+"string.regexp" = "yellow_{i}"
+"string.special.url" = {{ fg = "orange_{i}", modifiers = ["italic", "underlined"] }}
+"tag" = "magenta"
+"type" = "yellow"
+"type.builtin" = "cyan_{i}"
+"variable" = "fg1"
+"variable.builtin" = "red"
+"variable.parameter" = "cyan_{i}"
+"variable.other.member" = "blue"
+
+"markup.heading" = {{ fg = "blue_{i}", modifiers = ["bold"] }}
+"markup.bold" = {{ fg = "red", modifiers = ["bold"] }}
+"markup.italic" = {{ modifiers = ["italic"] }}
+"markup.strikethrough" = {{ modifiers = ["crossed_out"] }}
+"markup.link.url" = {{ fg = "orange_{i}", modifiers = ["italic", "underlined"] }}
+"markup.link.text" = "blue_{i}"
+"markup.list" = "cyan_{i}"
+"markup.quote" = "fg2"
+"markup.raw" = "cyan"
+
+"diff.plus" = "green"
+"diff.minus" = "red"
+"diff.delta" = "blue"
+
+"diagnostic.error" = {{ underline = {{ color = "red", style = "curl" }} }}
+"diagnostic.warning" = {{ underline = {{ color = "yellow", style = "curl" }} }}
+"diagnostic.info" = {{ underline = {{ color = "blue", style = "curl" }} }}
+"diagnostic.hint" = {{ underline = {{ color = "green", style = "curl" }} }}
+"error" = "red"
+"warning" = "yellow"
+"info" = "blue"
+"hint" = "green"
+
+"ui.background" = {{ bg = "bg1" }}
+"ui.text" = "fg1"
+"ui.text.focus" = {{ fg = "fg0", bg = "sel0" }}
+"ui.text.inactive" = "fg3"
+"ui.cursor" = {{ fg = "cur_fg", bg = "cur_bg" }}
+"ui.cursor.primary" = {{ fg = "cur_fg", bg = "cur_bg" }}
+"ui.cursor.match" = {{ fg = "yellow", modifiers = ["bold"] }}
+"ui.cursorline.primary" = {{ bg = "bg3" }}
+"ui.selection" = {{ bg = "sel0" }}
+"ui.selection.primary" = {{ bg = "sel1" }}
+"ui.linenr" = "fg3"
+"ui.linenr.selected" = {{ fg = "yellow", modifiers = ["bold"] }}
+"ui.gutter" = {{ bg = "bg1" }}
+"ui.statusline" = {{ fg = "fg2", bg = "bg0" }}
+"ui.statusline.inactive" = {{ fg = "fg3", bg = "bg0" }}
+"ui.statusline.normal" = {{ fg = "bg1", bg = "blue", modifiers = ["bold"] }}
+"ui.statusline.insert" = {{ fg = "bg1", bg = "green", modifiers = ["bold"] }}
+"ui.statusline.select" = {{ fg = "bg1", bg = "magenta", modifiers = ["bold"] }}
+"ui.bufferline" = {{ fg = "fg3", bg = "bg0" }}
+"ui.bufferline.active" = {{ fg = "fg1", bg = "bg2" }}
+"ui.bufferline.background" = {{ bg = "bg0" }}
+"ui.popup" = {{ fg = "fg1", bg = "bg0" }}
+"ui.window" = "bg0"
+"ui.help" = {{ fg = "fg1", bg = "bg0" }}
+"ui.menu" = {{ fg = "fg1", bg = "sel0" }}
+"ui.menu.selected" = {{ bg = "sel1" }}
+"ui.menu.scroll" = {{ fg = "fg3", bg = "bg2" }}
+"ui.virtual" = "bg4"
+"ui.virtual.whitespace" = "bg3"
+"ui.virtual.ruler" = {{ bg = "bg2" }}
+"ui.virtual.inlay-hint" = "comment"
+"ui.virtual.jump-label" = {{ fg = "red", modifiers = ["bold"] }}
+"ui.highlight" = {{ bg = "bg3" }}
+
+[palette]
+black = "{black}"
+red = "{red}"
+green = "{green}"
+yellow = "{yellow}"
+blue = "{blue}"
+magenta = "{magenta}"
+cyan = "{cyan}"
+white = "{white}"
+orange = "{orange}"
+pink = "{pink}"
+black_bright = "{black_bright}"
+red_bright = "{red_bright}"
+green_bright = "{green_bright}"
+yellow_bright = "{yellow_bright}"
+blue_bright = "{blue_bright}"
+magenta_bright = "{magenta_bright}"
+cyan_bright = "{cyan_bright}"
+white_bright = "{white_bright}"
+orange_bright = "{orange_bright}"
+pink_bright = "{pink_bright}"
+black_dim = "{black_dim}"
+red_dim = "{red_dim}"
+green_dim = "{green_dim}"
+yellow_dim = "{yellow_dim}"
+blue_dim = "{blue_dim}"
+magenta_dim = "{magenta_dim}"
+cyan_dim = "{cyan_dim}"
+white_dim = "{white_dim}"
+orange_dim = "{orange_dim}"
+pink_dim = "{pink_dim}"
+bg0 = "{bg0}"
+bg1 = "{bg1}"
+bg2 = "{bg2}"
+bg3 = "{bg3}"
+bg4 = "{bg4}"
+fg0 = "{fg0}"
+fg1 = "{fg1}"
+fg2 = "{fg2}"
+fg3 = "{fg3}"
+sel0 = "{sel0}"
+sel1 = "{sel1}"
+cur_bg = "{cur_bg}"
+cur_fg = "{cur_fg}"
+comment = "{comment}"
+"###,
+        theme_name = theme.name,
+        black = c.base.black,
+        red = c.base.red,
+        green = c.base.green,
+        yellow = c.base.yellow,
+        blue = c.base.blue,
+        magenta = c.base.magenta,
+        cyan = c.base.cyan,
+        white = c.base.white,
+        orange = c.base.orange,
+        pink = c.base.pink,
+        black_bright = c.bright.black,
+        red_bright = c.bright.red,
+        green_bright = c.bright.green,
+        yellow_bright = c.bright.yellow,
+        blue_bright = c.bright.blue,
+        magenta_bright = c.bright.magenta,
+        cyan_bright = c.bright.cyan,
+        white_bright = c.bright.white,
+        orange_bright = c.bright.orange,
+        pink_bright = c.bright.pink,
+        black_dim = c.dim.black,
+        red_dim = c.dim.red,
+        green_dim = c.dim.green,
+        yellow_dim = c.dim.yellow,
+        blue_dim = c.dim.blue,
+        magenta_dim = c.dim.magenta,
+        cyan_dim = c.dim.cyan,
+        white_dim = c.dim.white,
+        orange_dim = c.dim.orange,
+        pink_dim = c.dim.pink,
+        bg0 = c.bg[0],
+        bg1 = c.bg[1],
+        bg2 = c.bg[2],
+        bg3 = c.bg[3],
+        bg4 = c.bg[4],
+        fg0 = c.fg[0],
+        fg1 = c.fg[1],
+        fg2 = c.fg[2],
+        fg3 = c.fg[3],
+        sel0 = c.alt_selection[0],
+        sel1 = c.alt_selection[1],
+        cur_bg = c.cursor.bg,
+        cur_fg = c.cursor.fg,
+        comment = c.comment,
+    );
+
+    let themes_dir = path.parent().unwrap_or(Path::new(".")).join("themes");
+    utils::write_file(themes_dir.join(format!("{THEME_NAME}.toml")), content)?;
+
+    set_theme_in_config(path)
+}
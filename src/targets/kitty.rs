@@ -0,0 +1,147 @@
+use crate::utils;
+use recol_lib as lib;
+use std::{
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+/// Name of the optional theme file that `kitty.conf` may `include`.
+/// When such an include is present, colors are written there instead of
+/// into the main config.
+const THEME_INCLUDE_FILE: &str = "recol-theme.conf";
+
+#[derive(Debug)]
+enum ConfigLine {
+    KeyValue((String, String)),
+    Comment(String),
+    Any(String),
+    Empty,
+}
+
+fn read_config(path: impl AsRef<Path>) -> io::Result<Vec<ConfigLine>> {
+    let content = utils::read_to_string(path)?;
+    let reader = content.as_bytes();
+
+    let mut lines = Vec::with_capacity(32);
+    for line in reader.lines() {
+        let line = line?.trim().to_string();
+        if line.is_empty() {
+            lines.push(ConfigLine::Empty);
+        } else if line.starts_with('#') {
+            lines.push(ConfigLine::Comment(line));
+        } else if let Some((k, v)) = line.split_once(char::is_whitespace) {
+            lines.push(ConfigLine::KeyValue((k.into(), v.trim().into())));
+        } else {
+            lines.push(ConfigLine::Any(line));
+        }
+    }
+
+    Ok(lines)
+}
+
+fn write_config(path: impl AsRef<Path>, lines: &[ConfigLine]) -> io::Result<()> {
+    let mut writer = Vec::new();
+
+    for line in lines {
+        match line {
+            ConfigLine::KeyValue((k, v)) => writeln!(writer, "{k} {v}")?,
+            ConfigLine::Comment(c) => writeln!(writer, "{}", c)?,
+            ConfigLine::Any(l) => writeln!(writer, "{}", l)?,
+            ConfigLine::Empty => writeln!(writer)?,
+        }
+    }
+
+    utils::write_file(path, writer)
+}
+
+#[inline(always)]
+fn replace_or_add_key_value(lines: &mut Vec<ConfigLine>, k: &str, v: String) {
+    if let Some(ConfigLine::KeyValue((_, rv))) = lines.iter_mut().rev().find(|e| {
+        if let ConfigLine::KeyValue((rk, _)) = e {
+            return rk == k;
+        }
+        false
+    }) {
+        *rv = v;
+    } else {
+        lines.push(ConfigLine::KeyValue((k.into(), v)));
+    };
+}
+
+/// Returns the path of an `include recol-theme.conf` directive, resolved
+/// relative to the directory of `path`.
+fn theme_include_path(path: &Path, lines: &[ConfigLine]) -> Option<PathBuf> {
+    lines.iter().find_map(|l| match l {
+        ConfigLine::KeyValue((k, v))
+            if k == "include" && Path::new(v).file_name() == Some(THEME_INCLUDE_FILE.as_ref()) =>
+        {
+            let include = PathBuf::from(v);
+            if include.is_absolute() {
+                Some(include)
+            } else {
+                Some(path.parent().unwrap_or(Path::new(".")).join(include))
+            }
+        }
+        _ => None,
+    })
+}
+
+fn set_theme_colors(lines: &mut Vec<ConfigLine>, theme: &lib::Theme) {
+    let c = &theme.colors;
+
+    replace_or_add_key_value(lines, "background", c.bg.to_string());
+    replace_or_add_key_value(lines, "foreground", c.fg.to_string());
+    replace_or_add_key_value(lines, "selection_background", c.selection.bg.to_string());
+    replace_or_add_key_value(lines, "selection_foreground", c.selection.fg.to_string());
+    replace_or_add_key_value(lines, "cursor", c.cursor.bg.to_string());
+    replace_or_add_key_value(lines, "cursor_text_color", c.cursor.fg.to_string());
+
+    let palette = [
+        &c.base.black,
+        &c.base.red,
+        &c.base.green,
+        &c.base.yellow,
+        &c.base.blue,
+        &c.base.magenta,
+        &c.base.cyan,
+        &c.base.white,
+        &c.bright.black,
+        &c.bright.red,
+        &c.bright.green,
+        &c.bright.yellow,
+        &c.bright.blue,
+        &c.bright.magenta,
+        &c.bright.cyan,
+        &c.bright.white,
+    ];
+    for (i, color) in palette.into_iter().enumerate() {
+        replace_or_add_key_value(lines, &format!("color{i}"), color.to_string());
+    }
+}
+
+pub fn write_theme_to_config(path: impl AsRef<Path>, theme: &lib::Theme) -> io::Result<()> {
+    let path = path.as_ref();
+    let mut lines = read_config(path)?;
+
+    if let Some(include) = theme_include_path(path, &lines) {
+        let mut theme_lines = if include.is_file() {
+            read_config(&include)?
+        } else {
+            Vec::new()
+        };
+        set_theme_colors(&mut theme_lines, theme);
+        return write_config(include, &theme_lines);
+    }
+
+    set_theme_colors(&mut lines, theme);
+
+    write_config(path, &lines)
+}
+
+pub fn set_font_to_config(path: impl AsRef<Path>, font: String) -> io::Result<()> {
+    let mut lines = read_config(&path)?;
+
+    replace_or_add_key_value(&mut lines, "font_family", font);
+
+    write_config(path, &lines)
+}
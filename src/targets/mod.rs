@@ -3,20 +3,32 @@ use std::{fmt, path::PathBuf};
 
 use recol_lib as lib;
 
-mod alacritty;
+pub mod alacritty;
+mod foot;
 mod ghostty;
+mod helix;
+mod kitty;
 mod nvim;
-mod vim;
+mod template;
+mod tmux;
+pub mod vim;
 mod wezterm;
+mod zellij;
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
-pub const ALL_TARGETS: [Target; 5] = [
+pub const ALL_TARGETS: [Target; 11] = [
     Target::Ghostty,
     Target::Alacritty,
     Target::Wezterm,
+    Target::Kitty,
+    Target::Foot,
+    Target::Tmux,
+    Target::Zellij,
     Target::Nvim,
     Target::Vim,
+    Target::Helix,
+    Target::Template,
 ];
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
@@ -26,8 +38,14 @@ pub enum Target {
     Ghostty,
     Alacritty,
     Wezterm,
+    Kitty,
+    Foot,
+    Tmux,
+    Zellij,
     Nvim,
     Vim,
+    Helix,
+    Template,
 }
 
 impl fmt::Display for Target {
@@ -37,8 +55,14 @@ impl fmt::Display for Target {
             Target::Ghostty => "ghostty",
             Target::Alacritty => "alacritty",
             Target::Wezterm => "wezterm",
+            Target::Kitty => "kitty",
+            Target::Foot => "foot",
+            Target::Tmux => "tmux",
+            Target::Zellij => "zellij",
             Target::Nvim => "neovim",
             Target::Vim => "vim",
+            Target::Helix => "helix",
+            Target::Template => "template",
         };
         write!(f, "{s}")
     }
@@ -52,13 +76,29 @@ impl std::str::FromStr for Target {
             "g" | "gt" | "ghostty" => Ok(Self::Ghostty),
             "a" | "at" | "alacritty" => Ok(Self::Alacritty),
             "w" | "wt" | "wezterm" => Ok(Self::Wezterm),
+            "k" | "kt" | "kitty" => Ok(Self::Kitty),
+            "ft" | "foot" => Ok(Self::Foot),
+            "tm" | "tmux" => Ok(Self::Tmux),
+            "z" | "zj" | "zellij" => Ok(Self::Zellij),
             "n" | "nv" | "nvi" | "nvim" | "neovim" => Ok(Self::Nvim),
             "v" | "vi" | "vim" => Ok(Self::Vim),
+            "hx" | "helix" => Ok(Self::Helix),
+            "tp" | "tmpl" | "template" => Ok(Self::Template),
             _ => Err(()),
         }
     }
 }
 
+/// Colors for xterm indices 16–255, from
+/// [`lib::ColorScheme::extended_palette`].
+pub fn indexed_colors(c: &lib::ColorScheme) -> impl Iterator<Item = (usize, lib::CssColor)> {
+    c.extended_palette()
+        .into_iter()
+        .map(|c| c.css())
+        .enumerate()
+        .map(|(i, c)| (lib::EXTENDED_PALETTE_START + i, c))
+}
+
 #[inline(always)]
 fn home_dir() -> std::path::PathBuf {
     std::env::home_dir().unwrap()
@@ -71,8 +111,14 @@ impl Target {
                 Target::Ghostty => ghostty::write_theme_to_config(&path, t)?,
                 Target::Alacritty => alacritty::write_theme_to_config(&path, t)?,
                 Target::Wezterm => wezterm::write_theme_to_config(&path, t)?,
+                Target::Kitty => kitty::write_theme_to_config(&path, t)?,
+                Target::Foot => foot::write_theme_to_config(&path, t)?,
+                Target::Tmux => tmux::write_theme_to_config(&path, t)?,
+                Target::Zellij => zellij::write_theme_to_config(&path, t)?,
                 Target::Nvim => nvim::write_theme_to_config(&path, t)?,
                 Target::Vim => vim::write_theme_to_config(&path, t)?,
+                Target::Helix => helix::write_theme_to_config(&path, t)?,
+                Target::Template => template::write_theme_to_config(&path, t)?,
                 Target::None => {}
             }
         }
@@ -85,6 +131,8 @@ impl Target {
                 Target::Ghostty => ghostty::set_font_to_config(&path, font_name.into())?,
                 Target::Alacritty => alacritty::set_font_to_config(&path, font_name.into())?,
                 Target::Wezterm => {} // wezterm::set_font_to_config(&path, t)?,
+                Target::Kitty => kitty::set_font_to_config(&path, font_name.into())?,
+                Target::Foot => foot::set_font_to_config(&path, font_name.into())?,
                 _ => {}
             }
         }
@@ -157,6 +205,69 @@ impl Target {
                 }
                 None
             }
+            Target::Kitty => {
+                if let Ok(var) = std::env::var("KITTY_CONFIG_DIRECTORY") {
+                    let path = PathBuf::from(var).join("kitty.conf");
+                    if path.is_file() {
+                        return Some(path);
+                    }
+                }
+                let path = prefix.join("kitty/kitty.conf");
+                if path.is_file() {
+                    return Some(path);
+                }
+                #[cfg(target_os = "macos")]
+                {
+                    let path = home_dir().join("Library/Preferences/kitty/kitty.conf");
+                    if path.is_file() {
+                        return Some(path);
+                    }
+                }
+                None
+            }
+            Target::Foot => {
+                let path = prefix.join("foot/foot.ini");
+                if path.is_file() {
+                    return Some(path);
+                }
+                if let Ok(dirs) = std::env::var("XDG_CONFIG_DIRS") {
+                    for dir in dirs.split(':').filter(|s| !s.is_empty()) {
+                        let path = PathBuf::from(dir).join("foot/foot.ini");
+                        if path.is_file() {
+                            return Some(path);
+                        }
+                    }
+                }
+                let path = PathBuf::from("/etc/xdg/foot/foot.ini");
+                if path.is_file() {
+                    return Some(path);
+                }
+                None
+            }
+            Target::Tmux => {
+                let path = home_dir().join(".tmux.conf");
+                if path.is_file() {
+                    return Some(path);
+                }
+                let path = prefix.join("tmux/tmux.conf");
+                if path.is_file() {
+                    return Some(path);
+                }
+                None
+            }
+            Target::Zellij => {
+                if let Ok(var) = std::env::var("ZELLIJ_CONFIG_DIR") {
+                    let path = PathBuf::from(var).join("config.kdl");
+                    if path.is_file() {
+                        return Some(path);
+                    }
+                }
+                let path = prefix.join("zellij/config.kdl");
+                if path.is_file() {
+                    return Some(path);
+                }
+                None
+            }
             Target::Nvim => {
                 let path = prefix.join("nvim/init.lua");
                 if path.is_file() {
@@ -185,6 +296,20 @@ impl Target {
                 }
                 None
             }
+            Target::Helix => {
+                let path = prefix.join("helix/config.toml");
+                if path.is_file() {
+                    return Some(path);
+                }
+                None
+            }
+            Target::Template => {
+                let path = prefix.join("recol/templates");
+                if path.is_dir() {
+                    return Some(path);
+                }
+                None
+            }
             Target::None => None,
         }
     }
@@ -196,7 +321,7 @@ pub fn apply_theme(args: &Args, theme: &lib::Theme) -> Result<()> {
     } else {
         args.targets.as_slice()
     } {
-        target.apply_theme(&theme)?;
+        target.apply_theme(theme)?;
     }
 
     Ok(())
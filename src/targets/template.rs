@@ -0,0 +1,185 @@
+//! User-defined templates rendered with the theme colors.
+//!
+//! Every `*.tmpl` file in the templates directory is rendered and written to
+//! the output path listed for it in `templates.toml` (same directory):
+//!
+//! ```toml
+//! "waybar.tmpl" = "~/.config/waybar/colors.css"
+//! "rofi.tmpl" = "~/.config/rofi/colors.rasi"
+//! ```
+//!
+//! Templates without an entry are skipped.
+//!
+//! # Placeholders
+//!
+//! `{{path | filter | ...}}` where `path` addresses the
+//! [`AdvancedColorScheme`](lib::AdvancedColorScheme) fields, e.g. `bg`,
+//! `fg[2]`, `base.red`, `bright.blue`, `dim.cyan`, `selection.bg`,
+//! `cursor.fg`, `alt_selection[0]`, `diff.add`, `comment`, plus `name` and
+//! `is_light`. Bare `bg` and `fg` resolve to the main color (`[1]`); other
+//! arrays need an index.
+//!
+//! Filters: `strip_hash` (`1a2b3c`), `rgb` (`26, 43, 60`), `upper`.
+//! Unknown placeholders are left untouched.
+
+use crate::utils;
+use recol_lib as lib;
+use serde_json::Value;
+use std::{collections::HashMap, fs, io, path::Path};
+
+const TEMPLATE_EXT: &str = "tmpl";
+const MANIFEST_FILE: &str = "templates.toml";
+
+fn lookup<'a>(ctx: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut value = ctx;
+    for part in path.split('.') {
+        let (key, index) = match part.split_once('[') {
+            Some((key, rest)) => (key, Some(rest.strip_suffix(']')?.parse::<usize>().ok()?)),
+            None => (part, None),
+        };
+        value = value.get(key)?;
+        if let Some(i) = index {
+            value = value.get(i)?;
+        }
+    }
+    // Bare `bg` and `fg` are gradients; their main color sits at index 1.
+    if matches!(path, "bg" | "fg") {
+        return value.get(1);
+    }
+    Some(value)
+}
+
+fn apply_filter(value: String, filter: &str) -> Option<String> {
+    match filter {
+        "strip_hash" => Some(value.trim_start_matches('#').to_string()),
+        "rgb" => {
+            let (r, g, b) = value.parse::<lib::Color>().ok()?.rgb();
+            Some(format!("{r}, {g}, {b}"))
+        }
+        "upper" => Some(value.to_uppercase()),
+        _ => None,
+    }
+}
+
+fn render_placeholder(ctx: &Value, expr: &str) -> Option<String> {
+    let mut parts = expr.split('|').map(str::trim);
+    let value = match lookup(ctx, parts.next()?)? {
+        Value::String(s) => s.clone(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    parts.try_fold(value, apply_filter)
+}
+
+fn render(template: &str, ctx: &Value) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        match render_placeholder(ctx, &after[..end]) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+
+    out
+}
+
+pub fn write_theme_to_config(dir: impl AsRef<Path>, theme: &lib::Theme) -> io::Result<()> {
+    let dir = dir.as_ref();
+
+    let manifest = match fs::read_to_string(dir.join(MANIFEST_FILE)) {
+        Ok(content) => toml::from_str::<HashMap<String, String>>(&content)
+            .map_err(|e| utils::io_other_error(format!("{MANIFEST_FILE}: {e}")))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    let mut ctx = serde_json::to_value(theme.colors.clone().into_advanced(None))
+        .map_err(utils::io_other_error)?;
+    ctx["name"] = Value::from(theme.name.as_str());
+    ctx["is_light"] = Value::from(theme.is_light);
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != TEMPLATE_EXT) {
+            continue;
+        }
+        let Some(output) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| manifest.get(n))
+        else {
+            continue;
+        };
+
+        let content = render(&fs::read_to_string(&path)?, &ctx);
+
+        let output = utils::expand_home(output);
+        utils::write_file(output, content)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ctx() -> Value {
+        json!({
+            "name": "Test",
+            "bg": ["#000000", "#101010", "#202020"],
+            "fg": ["#c0c0c0", "#d0d0d0", "#e0e0e0"],
+            "alt_selection": ["#303030", "#404040"],
+            "base": { "red": "#1a2b3c" },
+        })
+    }
+
+    #[test]
+    fn renders_paths_and_indexes() {
+        assert_eq!(render("{{ base.red }};", &ctx()), "#1a2b3c;");
+        assert_eq!(
+            render("{{fg[2]}} {{alt_selection[0]}}", &ctx()),
+            "#e0e0e0 #303030"
+        );
+        assert_eq!(
+            render("{{bg}} {{fg}} {{name}}", &ctx()),
+            "#101010 #d0d0d0 Test"
+        );
+    }
+
+    #[test]
+    fn applies_filters() {
+        assert_eq!(render("{{base.red | strip_hash}}", &ctx()), "1a2b3c");
+        assert_eq!(render("{{base.red | rgb}}", &ctx()), "26, 43, 60");
+        assert_eq!(render("{{base.red | upper}}", &ctx()), "#1A2B3C");
+        assert_eq!(
+            render("{{base.red | strip_hash | upper}}", &ctx()),
+            "1A2B3C"
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_placeholders_verbatim() {
+        for template in [
+            "{{base.blue}}",
+            "{{alt_selection}}",
+            "{{fg[9]}}",
+            "{{base.red | shout}}",
+            "{{base.red",
+        ] {
+            assert_eq!(render(template, &ctx()), template);
+        }
+    }
+}
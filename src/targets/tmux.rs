@@ -0,0 +1,49 @@
+use crate::utils;
+use recol_lib as lib;
+use std::{io, path::Path};
+
+pub fn write_theme_to_config(path: impl AsRef<Path>, theme: &lib::Theme) -> io::Result<()> {
+    let c = theme.colors.clone().into_advanced(None);
+
+    let content = format!(
+        r#"
+# {theme_name}
+set -g status-style "fg={fg2},bg={bg0}"
+set -g status-left-style "fg={bg1},bg={blue}"
+set -g status-right-style "fg={fg2},bg={bg2}"
+set -g window-status-style "fg={fg3},bg={bg0}"
+set -g window-status-current-style "fg={fg0},bg={bg3},bold"
+set -g window-status-activity-style "fg={yellow},bg={bg0}"
+set -g window-status-bell-style "fg={red},bg={bg0}"
+set -g pane-border-style "fg={bg3}"
+set -g pane-active-border-style "fg={blue}"
+set -g display-panes-colour "{bg3}"
+set -g display-panes-active-colour "{blue}"
+set -g message-style "fg={fg1},bg={bg2}"
+set -g message-command-style "fg={fg1},bg={bg2}"
+set -g mode-style "fg={sel_fg},bg={sel_bg}"
+set -g clock-mode-colour "{blue}""#,
+        theme_name = theme.name,
+        bg0 = c.bg[0],
+        bg1 = c.bg[1],
+        bg2 = c.bg[2],
+        bg3 = c.bg[3],
+        fg0 = c.fg[0],
+        fg1 = c.fg[1],
+        fg2 = c.fg[2],
+        fg3 = c.fg[3],
+        sel_bg = c.selection.bg,
+        sel_fg = c.selection.fg,
+        red = c.base.red,
+        yellow = c.base.yellow,
+        blue = c.base.blue,
+    );
+
+    utils::write_content_inside_text_block(
+        path,
+        content.as_bytes(),
+        ("# recol:start", "# recol:end"),
+    )?;
+
+    Ok(())
+}
@@ -2,7 +2,9 @@ use crate::utils;
 use recol_lib::{self as lib, CssColor};
 use std::{io, path::Path};
 
-pub fn write_theme_to_config(path: impl AsRef<Path>, theme: &lib::Theme) -> io::Result<()> {
+/// Vim script that sets up all highlight groups for `theme`; also usable as a
+/// standalone `colors/*.vim` file.
+pub fn colorscheme(theme: &lib::Theme) -> String {
     let c = theme.colors.clone().into_advanced(None);
 
     let pick = |bright: &CssColor, dim: &CssColor| -> CssColor {
@@ -38,7 +40,7 @@ pub fn write_theme_to_config(path: impl AsRef<Path>, theme: &lib::Theme) -> io::
 
     let background = if theme.is_light { "light" } else { "dark" };
 
-    let content = format!(
+    format!(
         r#"
 hi clear
 if exists("syntax_on")
@@ -196,7 +198,11 @@ hi diffIndexLine guifg={syn_preproc}"#,
         git_add = git_add,
         git_removed = git_removed,
         git_changed = git_changed,
-    );
+    )
+}
+
+pub fn write_theme_to_config(path: impl AsRef<Path>, theme: &lib::Theme) -> io::Result<()> {
+    let content = colorscheme(theme);
 
     utils::write_content_inside_text_block(
         path,
@@ -1,13 +1,14 @@
+use crate::utils;
 use recol_lib as lib;
 use std::io::{self, BufRead, Write};
-use std::{fs, path::Path};
+use std::path::Path;
 
 pub fn write_theme_to_config(path: impl AsRef<Path>, theme: &lib::Theme) -> io::Result<()> {
     let path = path.as_ref();
 
     // --- Parse existing config ---
-    let file = fs::File::open(path)?;
-    let reader = io::BufReader::new(file);
+    let content = utils::read_to_string(path)?;
+    let reader = content.as_bytes();
 
     let mut preamble = Vec::new();
     let mut config_var = Vec::new();
@@ -58,6 +59,9 @@ pub fn write_theme_to_config(path: impl AsRef<Path>, theme: &lib::Theme) -> io::
     }
 
     // --- Build theme block ---
+    let indexed = super::indexed_colors(&theme.colors)
+        .map(|(i, c)| format!("    [{i}] = \"{c}\",\n"))
+        .collect::<String>();
     let colors = theme.colors.clone().into_advanced(None);
     let theme_block = format!(
         r###"-- {theme_name}
@@ -89,6 +93,8 @@ config.colors.brights = {{
     "{cyan_bright}",
     "{white_bright}",
 }}
+config.colors.indexed = {{
+{indexed}}}
 config.colors.split = "{fg2}"
 config.colors.scrollbar_thumb = "{bg2}"
 config.colors.tab_bar = {{
@@ -144,11 +150,11 @@ config.colors.tab_bar = {{
         white_bright = colors.bright.white,
     );
 
-    let mut file = fs::File::create(path)?;
+    let mut buf = Vec::new();
 
     // Preamble (strip trailing newline added by the loop).
     preamble.pop();
-    file.write_all(&preamble)?;
+    buf.write_all(&preamble)?;
 
     if in_mark_block {
         // file.write_all(&mark_block)?;
@@ -157,17 +163,17 @@ config.colors.tab_bar = {{
     // Config-var section, or a fresh empty table when absent.
     const PASSTHROUGH_CONFIG: &[u8] = b"local config = config\n";
     if config_var.is_empty() {
-        writeln!(&mut file, "\nlocal config = {{}}")?;
+        writeln!(&mut buf, "\nlocal config = {{}}")?;
     } else if config_var != PASSTHROUGH_CONFIG {
-        writeln!(&mut file)?;
-        file.write_all(&config_var)?;
+        writeln!(&mut buf)?;
+        buf.write_all(&config_var)?;
     }
 
     // Inject new theme block.
-    writeln!(&mut file, "-- recol:start")?;
-    writeln!(&mut file, "{theme_block}")?;
-    writeln!(&mut file, "-- recol:end")?;
-    writeln!(&mut file, "\nreturn config")?;
+    writeln!(&mut buf, "-- recol:start")?;
+    writeln!(&mut buf, "{theme_block}")?;
+    writeln!(&mut buf, "-- recol:end")?;
+    writeln!(&mut buf, "\nreturn config")?;
 
-    file.flush()
+    utils::write_file(path, buf)
 }
@@ -0,0 +1,83 @@
+use crate::utils;
+use recol_lib as lib;
+use std::{
+    io::{self, BufRead},
+    path::Path,
+};
+
+const THEME_NAME: &str = "recol";
+
+/// Points the top-level `theme` node at the generated theme, keeping the
+/// rest of the config intact. Nodes nested inside blocks are left alone.
+fn set_theme_in_config(path: &Path) -> io::Result<()> {
+    let content = utils::read_to_string(path)?;
+    let reader = content.as_bytes();
+
+    let mut lines = Vec::new();
+    let mut replaced = false;
+    let mut depth = 0isize;
+
+    for line in reader.lines() {
+        let line = line?;
+        let trimmed = line.trim_start();
+        if depth == 0 && (trimmed.starts_with("theme ") || trimmed.starts_with("theme\t")) {
+            if !replaced {
+                lines.push(format!("theme \"{THEME_NAME}\""));
+                replaced = true;
+            }
+            continue;
+        }
+        if !trimmed.starts_with("//") {
+            depth += line.matches('{').count() as isize - line.matches('}').count() as isize;
+        }
+        lines.push(line);
+    }
+    if !replaced {
+        lines.push(format!("theme \"{THEME_NAME}\""));
+    }
+
+    utils::write_file(path, lines.join("\n") + "\n")
+}
+
+pub fn write_theme_to_config(path: impl AsRef<Path>, theme: &lib::Theme) -> io::Result<()> {
+    let path = path.as_ref();
+    let c = &theme.colors;
+
+    let content = format!(
+        r#"// {theme_name}
+// Generated by recol. Changes will be overwritten.
+themes {{
+    {THEME_NAME} {{
+        fg "{fg}"
+        bg "{bg}"
+        black "{black}"
+        red "{red}"
+        green "{green}"
+        yellow "{yellow}"
+        blue "{blue}"
+        magenta "{magenta}"
+        cyan "{cyan}"
+        white "{white}"
+        orange "{orange}"
+    }}
+}}
+"#,
+        theme_name = theme.name,
+        fg = c.fg,
+        bg = c.bg,
+        black = c.base.black,
+        red = c.base.red,
+        green = c.base.green,
+        yellow = c.base.yellow,
+        blue = c.base.blue,
+        magenta = c.base.magenta,
+        cyan = c.base.cyan,
+        white = c.base.white,
+        orange = c.base.orange,
+    );
+
+    let themes_dir = path.parent().unwrap_or(Path::new(".")).join("themes");
+    utils::write_file(themes_dir.join(format!("{THEME_NAME}.kdl")), content)?;
+
+    set_theme_in_config(path)
+}
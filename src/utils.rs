@@ -1,9 +1,144 @@
+use crate::store;
+use recol_lib as lib;
 use std::{
+    collections::BTreeMap,
     fs,
     io::{self, BufRead, Write},
-    path::Path,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
 };
 
+/// Symlink hops [`write_atomic`] follows before giving up, as in Linux.
+const MAX_SYMLINKS: usize = 40;
+
+/// Files written under `--dry-run`, kept in memory instead of on disk;
+/// `None` marks a removed file.
+static DRY_RUN: OnceLock<Mutex<BTreeMap<PathBuf, Option<Vec<u8>>>>> = OnceLock::new();
+
+/// From now on [`write_file`], [`replace_file`] and [`remove_file`] only
+/// record the change, see [`take_dry_run_writes`].
+pub fn enable_dry_run() {
+    let _ = DRY_RUN.set(Mutex::default());
+}
+
+pub fn is_dry_run() -> bool {
+    DRY_RUN.get().is_some()
+}
+
+/// Reads a file, seeing what earlier [`write_file`] calls left there even
+/// under `--dry-run`.
+pub fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+    if let Some(files) = DRY_RUN.get() {
+        let path = std::path::absolute(&path)?;
+        match files.lock().unwrap().get(&path) {
+            Some(Some(content)) => {
+                return String::from_utf8(content.clone()).map_err(io_other_error)
+            }
+            Some(None) => return Err(io::ErrorKind::NotFound.into()),
+            None => {}
+        }
+    }
+    fs::read_to_string(path)
+}
+
+/// Writes a config file for a target, creating missing parent dirs. The
+/// previous contents are backed up first (see [`store::backup`]).
+pub fn write_file(path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> io::Result<()> {
+    store::backup(&path)?;
+    replace_file(path, content)
+}
+
+/// [`write_file`] without the backup, for putting backups back.
+pub fn replace_file(path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref();
+    if let Some(files) = DRY_RUN.get() {
+        let path = std::path::absolute(path)?;
+        files
+            .lock()
+            .unwrap()
+            .insert(path, Some(content.as_ref().to_vec()));
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_atomic(path, content)
+}
+
+/// Removes a file, or under `--dry-run` only records the removal.
+pub fn remove_file(path: impl AsRef<Path>) -> io::Result<()> {
+    if let Some(files) = DRY_RUN.get() {
+        let path = std::path::absolute(path)?;
+        files.lock().unwrap().insert(path, None);
+        return Ok(());
+    }
+    fs::remove_file(path)
+}
+
+/// Replaces a file so that a crash leaves either the old or the new
+/// contents, never a truncated file: the data goes to a temp file in the
+/// same directory, is synced and then renamed over the original. Symlinks
+/// (e.g. stowed dotfiles) are followed and their target is replaced, and
+/// the permissions of an existing file are kept.
+fn write_atomic(path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> io::Result<()> {
+    let mut target = path.as_ref().to_path_buf();
+    for _ in 0..MAX_SYMLINKS {
+        let Ok(link) = fs::read_link(&target) else {
+            break;
+        };
+        target = target.parent().unwrap_or(Path::new("")).join(link);
+    }
+
+    let dir = match target.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| io_other_error(format!("not a file: {}", target.display())))?;
+    let tmp = dir.join(format!(
+        ".{}.recol-{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+    let permissions = fs::metadata(&target).ok().map(|m| m.permissions());
+
+    let _ = fs::remove_file(&tmp);
+    let result = (|| -> io::Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp)?;
+        file.write_all(content.as_ref())?;
+        if let Some(permissions) = permissions {
+            file.set_permissions(permissions)?;
+        }
+        file.sync_all()?;
+        fs::rename(&tmp, &target)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result?;
+
+    // Make the rename itself durable.
+    #[cfg(unix)]
+    if let Ok(dir) = fs::File::open(dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+/// The files changed under `--dry-run` so far, with their new contents
+/// (`None` if removed).
+pub fn take_dry_run_writes() -> BTreeMap<PathBuf, Option<Vec<u8>>> {
+    DRY_RUN
+        .get()
+        .map(|files| std::mem::take(&mut *files.lock().unwrap()))
+        .unwrap_or_default()
+}
+
 pub fn write_content_inside_text_block<P>(
     path: P,
     content: &[u8],
@@ -12,15 +147,15 @@ pub fn write_content_inside_text_block<P>(
 where
     P: AsRef<Path>,
 {
-    let file = fs::File::open(&path)?;
+    let existing = read_to_string(&path)?;
 
-    let reader = io::BufReader::new(file);
+    let reader = existing.as_bytes();
 
     let mut buf = Vec::new();
     let mut lines = reader.lines();
     let mut inserted = false;
 
-    while let Some(line) = lines.next() {
+    for line in lines.by_ref() {
         let line = line?;
         writeln!(&mut buf, "{}", &line)?;
         if line == mark.0 {
@@ -37,7 +172,7 @@ where
         let mut replace_buf = Vec::new();
         let mut found_end = false;
 
-        while let Some(line) = lines.next() {
+        for line in lines.by_ref() {
             let line = line?;
             writeln!(&mut replace_buf, "{}", &line)?;
             if line == mark.1 {
@@ -52,7 +187,7 @@ where
             buf.write_all(&replace_buf)?;
         }
 
-        while let Some(line) = lines.next() {
+        for line in lines {
             let line = line?;
             writeln!(&mut buf, "{}", &line)?;
         }
@@ -64,14 +199,34 @@ where
 
     buf.flush()?;
 
-    fs::write(&path, &buf)?;
-
-    Ok(())
+    write_file(&path, &buf)
 }
 
 pub fn io_other_error<E>(err: E) -> io::Error
 where
     E: Into<Box<dyn std::error::Error + Send + Sync>>,
 {
-    io::Error::new(io::ErrorKind::Other, err)
+    io::Error::other(err)
+}
+
+/// Expands a leading `~` to the user's home directory.
+pub fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => std::env::home_dir().unwrap_or_default().join(rest),
+        None if path == "~" => std::env::home_dir().unwrap_or_default(),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Decodes a PNG or JPEG, downscaled so large wallpapers stay cheap to
+/// cluster.
+pub fn read_image_pixels(path: impl AsRef<Path>) -> io::Result<Vec<lib::Color>> {
+    let image = image::open(path).map_err(io_other_error)?;
+    let pixels = image
+        .thumbnail(256, 256)
+        .to_rgb8()
+        .pixels()
+        .map(|p| lib::Color::from_rgb(p[0], p[1], p[2]))
+        .collect();
+    Ok(pixels)
 }
@@ -0,0 +1,27 @@
+use std::{env, fs, process::Command};
+
+#[test]
+fn audit_leaves_configs_alone() {
+    let home = env::temp_dir().join(format!("recol-audit-test-{}", std::process::id()));
+    let (config, state) = (home.join(".config"), home.join(".local/state"));
+    let kitty_conf = config.join("kitty/kitty.conf");
+    fs::create_dir_all(kitty_conf.parent().unwrap()).unwrap();
+    fs::create_dir_all(state.join("recol")).unwrap();
+    fs::write(&kitty_conf, "font_size 11\n").unwrap();
+    fs::write(state.join("recol/theme.history"), "Nord\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_recol"))
+        .arg("--audit")
+        .env_clear()
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", &config)
+        .env("XDG_STATE_HOME", &state)
+        .env("RECOL_ADJUST", "pal.hue=40")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&kitty_conf).unwrap(), "font_size 11\n");
+    assert!(!state.join("recol/backups").exists());
+    let _ = fs::remove_dir_all(&home);
+}
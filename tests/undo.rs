@@ -0,0 +1,42 @@
+use std::{env, fs, path::Path, process::Command};
+
+fn recol(home: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_recol"))
+        .args(args)
+        .env_clear()
+        .env("HOME", home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("XDG_STATE_HOME", home.join(".local/state"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "recol {args:?} failed");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn dry_run_undo_changes_nothing() {
+    let home = env::temp_dir().join(format!("recol-undo-test-{}", std::process::id()));
+    let config = home.join(".config");
+    let (ghostty, kitty) = (config.join("ghostty/config"), config.join("kitty"));
+    fs::create_dir_all(ghostty.parent().unwrap()).unwrap();
+    fs::create_dir_all(&kitty).unwrap();
+    fs::write(&ghostty, "font-size = 11\n").unwrap();
+    fs::write(kitty.join("kitty.conf"), "include recol-theme.conf\n").unwrap();
+
+    recol(&home, &["Nord"]);
+    let applied = fs::read_to_string(&ghostty).unwrap();
+    let created = kitty.join("recol-theme.conf");
+    assert!(created.is_file());
+
+    let diff = recol(&home, &["--undo", "--dry-run"]);
+
+    assert!(diff.contains(&format!("--- {}\n", ghostty.display())));
+    assert!(diff.contains("-background = #2e3440\n"));
+    assert!(diff.contains("+++ /dev/null\n"));
+    assert_eq!(fs::read_to_string(&ghostty).unwrap(), applied);
+    assert!(created.is_file());
+    let backups = home.join(".local/state/recol/backups");
+    assert_eq!(fs::read_dir(backups).unwrap().count(), 1);
+
+    let _ = fs::remove_dir_all(&home);
+}